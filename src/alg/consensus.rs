@@ -0,0 +1,72 @@
+//! Consensus growth: growing the same DAG many times under different tie-breaks (or costs),
+//! keeping the edges which most runs agree on.
+
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+use crate::parsing::{dag::PartialDag, interactome::Interactome, weight::Weight};
+
+use super::{
+    cost::Cost,
+    grow::{grow_k, GrowError, GrowthCache, GrowthOptions},
+};
+
+/// Grows `dag` up to `k` times in each of `runs` independent runs, where run `i` breaks
+/// ties between equal-cost candidates with the seed `i` (see `GrowthOptions::tie_break_seed`).
+/// Every run otherwise grows under `options`. Runs happen in parallel, on the current rayon thread pool.
+///
+/// Returns every (non-super) edge of the grown DAGs which appears in at least a `fraction` of the runs,
+/// along with the fraction of runs it appeared in. These are sorted by that fraction, from most to least frequent.
+pub fn consensus<C: Cost + Clone + Send + Sync>(
+    interactome: &Interactome<Weight>,
+    dag: &PartialDag<()>,
+    cost: &C,
+    options: &GrowthOptions,
+    k: usize,
+    runs: usize,
+    fraction: f64,
+) -> Result<Vec<(String, String, f64)>, GrowError> {
+    let grown_dags = (0..runs)
+        .into_par_iter()
+        .map(|run| {
+            let mut dag = dag.clone();
+            let mut cache = GrowthCache::lazy();
+            cache.options = GrowthOptions { tie_break_seed: Some(run as u64), ..options.clone() };
+
+            grow_k(interactome, &mut dag, &mut cache, &mut cost.clone(), k, None)?;
+
+            Ok(dag)
+        })
+        .collect::<Result<Vec<_>, GrowError>>()?;
+
+    // `edge_support` is already sorted by count, which the frequency preserves.
+    Ok(edge_support(&grown_dags)
+        .into_iter()
+        .map(|(a, b, count)| (a, b, count as f64 / runs as f64))
+        .filter(|(_, _, frequency)| *frequency >= fraction)
+        .collect())
+}
+
+/// Counts, for every (non-super) edge among `dags`, how many of them contain it. This merges DAGs grown from
+/// the same start under different settings (e.g. one per cost function), where edges most DAGs agree on are the
+/// most robust to that setting.
+///
+/// Edges are sorted by their support, from most to least, and then by name.
+pub fn edge_support(dags: &[PartialDag<()>]) -> Vec<(String, String, usize)> {
+    let mut counts: HashMap<(String, String), usize> = HashMap::new();
+    for dag in dags {
+        for (a, b, _) in dag.0.inner_network.graph.all_edges() {
+            if let (Some(a), Some(b)) = (a.left(), b.left()) {
+                let a = dag.0.inner_network.id_from_idx(a).cloned().unwrap();
+                let b = dag.0.inner_network.id_from_idx(b).cloned().unwrap();
+                *counts.entry((a, b)).or_default() += 1;
+            }
+        }
+    }
+
+    let mut edges = counts.into_iter().map(|((a, b), count)| (a, b, count)).collect::<Vec<_>>();
+    edges.sort_by(|(a_x, b_x, x), (a_y, b_y, y)| y.cmp(x).then_with(|| (a_x, b_x).cmp(&(a_y, b_y))));
+
+    edges
+}
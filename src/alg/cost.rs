@@ -1,3 +1,8 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
 use either::Either;
 use petgraph::algo::all_simple_paths;
 use xxhash_rust::xxh3::Xxh3Builder;
@@ -18,6 +23,53 @@ pub trait Cost {
         dag: &PartialDag<()>,
         nodes: &[Either<usize, SuperNode>],
     ) -> f64;
+
+    /// Like `Self::relative_cost_of`, but also returns the number of source-target paths which were enumerated
+    /// to find the cost, for costs which enumerate them (such as `PathCost`). This explains slow iterations
+    /// (see `GrowDiagnostics::paths_enumerated`). Other costs enumerate none, which is the default.
+    fn relative_cost_counting_paths(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> (f64, usize) {
+        (self.relative_cost_of(main, dag, nodes), 0)
+    }
+
+    /// Breaks `Self::relative_cost_of` down into the contribution of each edge along `nodes`
+    /// which is not already in the DAG, in path order, so that the contributions sum to the relative cost.
+    ///
+    /// Costs which don't decompose per-edge (such as `PathCost`) return no contributions, which is the default.
+    fn edge_contributions(
+        &mut self,
+        _main: &Interactome<Weight>,
+        _dag: &PartialDag<()>,
+        _nodes: &[Either<usize, SuperNode>],
+    ) -> Vec<EdgeContribution> {
+        vec![]
+    }
+
+    /// Called with every candidate path (`nodes`) which was considered, but lost out to a cheaper one,
+    /// before the winner is added to `dag`. Costs which adapt to the search (such as `AdaptiveCost`)
+    /// can use this to remember which paths were passed over. This does nothing by default.
+    fn rejected(&mut self, _dag: &PartialDag<()>, _nodes: &[Either<usize, SuperNode>]) {}
+}
+
+/// An edge `(a, b)` and its contribution to a path's relative cost. See `Cost::edge_contributions`.
+pub type EdgeContribution = (Either<usize, SuperNode>, Either<usize, SuperNode>, f64);
+
+/// An edge `(a, b)` between (possibly super) nodes.
+type Edge = (Either<usize, SuperNode>, Either<usize, SuperNode>);
+
+/// The edges along `nodes` which are not already in the DAG.
+fn new_edges<'a>(
+    dag: &'a PartialDag<()>,
+    nodes: &'a [Either<usize, SuperNode>],
+) -> impl Iterator<Item = Edge> + 'a {
+    nodes
+        .windows(2)
+        .filter(|edge| !dag.0.inner_network.graph.contains_edge(edge[0], edge[1]))
+        .map(|edge| (edge[0], edge[1]))
 }
 
 /// The **min edge cost** function: we simply minimize
@@ -50,10 +102,27 @@ impl Cost for EdgeCost {
 
         added_cost
     }
+
+    fn edge_contributions(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> Vec<EdgeContribution> {
+        new_edges(dag, nodes)
+            .map(|(source, target)| (source, target, main.inner_network.graph.edge_weight(source, target).unwrap().0))
+            .collect()
+    }
 }
 
 /// The **min paths cost** function: we try to minimize
 /// the weights of every single path provided in the new DAG.
+///
+/// A new edge can change the cost of many source-target paths at once, so this cost has no `Cost::edge_contributions`.
+///
+/// Every evaluation enumerates each source-target path of the grown DAG, which grows combinatorially as the DAG
+/// branches: `Cost::relative_cost_counting_paths` reports how many, to explain slow iterations.
+#[derive(Debug, Default, Clone, Copy)]
 pub struct PathCost;
 
 impl Cost for PathCost {
@@ -63,6 +132,15 @@ impl Cost for PathCost {
         dag: &PartialDag<()>,
         nodes: &[Either<usize, SuperNode>],
     ) -> f64 {
+        self.relative_cost_counting_paths(main, dag, nodes).0
+    }
+
+    fn relative_cost_counting_paths(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> (f64, usize) {
         let mut new_dag = dag.clone();
 
         for i in 0..nodes.len() - 1 {
@@ -80,8 +158,10 @@ impl Cost for PathCost {
         );
 
         let mut relative_cost = 0_f64;
+        let mut path_count = 0;
 
         for path in paths {
+            path_count += 1;
             for i in 0..path.len() - 1 {
                 let source = path[i];
                 let target = path[i + 1];
@@ -90,14 +170,534 @@ impl Cost for PathCost {
             }
         }
 
-        relative_cost
+        log::trace!("Enumerated {path_count} source-target paths.");
+
+        (relative_cost, path_count)
+    }
+}
+
+/// The **min node cost** function: we minimize the weights (see `Network::attach_node_weights`)
+/// of the nodes which the path adds to the DAG.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NodeCost;
+
+impl Cost for NodeCost {
+    fn relative_cost_of(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> f64 {
+        nodes
+            .iter()
+            .filter(|&&node| !dag.0.inner_network.graph.contains_node(node))
+            .filter_map(|node| node.left())
+            .map(|node| main.inner_network.node_weight(node))
+            .fold(0_f64, |cost, weight| cost + weight)
+    }
+
+    /// Each new node's weight is attributed to the new edge entering it. This is exact for grown paths,
+    /// which always start at a DAG node.
+    fn edge_contributions(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> Vec<EdgeContribution> {
+        new_edges(dag, nodes)
+            .map(|(source, target)| {
+                let weight = match target {
+                    Either::Left(node) if !dag.0.inner_network.graph.contains_node(target) => {
+                        main.inner_network.node_weight(node)
+                    },
+                    _ => 0.0,
+                };
+                (source, target, weight)
+            })
+            .collect()
+    }
+}
+
+/// Mixes `EdgeCost` and `NodeCost` as `alpha * edge_cost + (1 - alpha) * node_cost`,
+/// so `alpha = 1` only considers edge weights, and `alpha = 0` only considers node weights.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CombinedCost {
+    pub edge_cost: EdgeCost,
+    pub node_cost: NodeCost,
+    pub alpha: f64,
+}
+
+impl CombinedCost {
+    pub fn new(alpha: f64) -> Self {
+        Self {
+            edge_cost: EdgeCost,
+            node_cost: NodeCost,
+            alpha,
+        }
+    }
+}
+
+impl Cost for CombinedCost {
+    fn relative_cost_of(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> f64 {
+        let edge_cost = self.edge_cost.relative_cost_of(main, dag, nodes);
+        let node_cost = self.node_cost.relative_cost_of(main, dag, nodes);
+
+        self.alpha * edge_cost + (1.0 - self.alpha) * node_cost
+    }
+
+    fn edge_contributions(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> Vec<EdgeContribution> {
+        let edge_costs = self.edge_cost.edge_contributions(main, dag, nodes);
+        let node_costs = self.node_cost.edge_contributions(main, dag, nodes);
+
+        edge_costs
+            .into_iter()
+            .zip(node_costs)
+            .map(|((a, b, edge_cost), (_, _, node_cost))| {
+                (a, b, self.alpha * edge_cost + (1.0 - self.alpha) * node_cost)
+            })
+            .collect()
+    }
+}
+
+/// The number of edges along `nodes` which are not already in the DAG.
+fn new_edge_count(dag: &PartialDag<()>, nodes: &[Either<usize, SuperNode>]) -> usize {
+    new_edges(dag, nodes).count()
+}
+
+/// Adds `lambda` to the `base` cost for every edge the path adds to the DAG,
+/// discouraging (without forbidding) long detours.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LengthPenaltyCost<C: Cost> {
+    pub base: C,
+    pub lambda: f64,
+}
+
+impl<C: Cost> LengthPenaltyCost<C> {
+    pub fn new(base: C, lambda: f64) -> Self {
+        Self { base, lambda }
+    }
+}
+
+impl<C: Cost> Cost for LengthPenaltyCost<C> {
+    fn relative_cost_of(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> f64 {
+        self.relative_cost_counting_paths(main, dag, nodes).0
+    }
+
+    fn relative_cost_counting_paths(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> (f64, usize) {
+        let (cost, paths) = self.base.relative_cost_counting_paths(main, dag, nodes);
+        (cost + self.lambda * new_edge_count(dag, nodes) as f64, paths)
+    }
+
+    /// Adds `lambda` to each of the `base` cost's contributions, which are empty if the `base` cost has none.
+    fn edge_contributions(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> Vec<EdgeContribution> {
+        self.base
+            .edge_contributions(main, dag, nodes)
+            .into_iter()
+            .map(|(a, b, cost)| (a, b, cost + self.lambda))
+            .collect()
+    }
+
+    fn rejected(&mut self, dag: &PartialDag<()>, nodes: &[Either<usize, SuperNode>]) {
+        self.base.rejected(dag, nodes)
+    }
+}
+
+/// Discounts the `base` cost of paths which connect to recently added DAG nodes,
+/// so that growth stays around the same part of the DAG.
+///
+/// Each DAG node is stamped with the iteration it was first seen in (the initial DAG being iteration 0),
+/// where a new iteration starts whenever the DAG gains edges. A path whose most recent endpoint is `age`
+/// iterations old has its cost multiplied by `1 - decay^(age + 1)`, so a `decay` of 0 has no effect,
+/// and one closer to 1 prefers recent nodes more strongly. This state is tied to a single DAG.
+#[derive(Debug, Default, Clone)]
+pub struct RecencyCost<C: Cost> {
+    pub base: C,
+    pub decay: f64,
+    added_at: HashMap<Either<usize, SuperNode>, usize>,
+    iteration: usize,
+    seen_edges: Option<usize>,
+}
+
+impl<C: Cost> RecencyCost<C> {
+    pub fn new(base: C, decay: f64) -> Self {
+        Self { base, decay, added_at: HashMap::new(), iteration: 0, seen_edges: None }
+    }
+
+    /// Stamps any nodes new to `dag`, starting a new iteration if it changed since the last call.
+    fn observe(&mut self, dag: &PartialDag<()>) {
+        let graph = &dag.0.inner_network.graph;
+        if self.seen_edges == Some(graph.edge_count()) {
+            return;
+        }
+
+        if self.seen_edges.is_some() {
+            self.iteration += 1;
+        }
+        self.seen_edges = Some(graph.edge_count());
+
+        for node in graph.nodes() {
+            self.added_at.entry(node).or_insert(self.iteration);
+        }
+    }
+
+    /// What the `base` cost of `nodes` is multiplied by.
+    fn discount(&self, nodes: &[Either<usize, SuperNode>]) -> f64 {
+        let age = [nodes.first(), nodes.last()]
+            .into_iter()
+            .flatten()
+            .filter_map(|node| self.added_at.get(node))
+            .map(|&added_at| self.iteration - added_at)
+            .min();
+
+        match age {
+            Some(age) => 1.0 - self.decay.powi(age as i32 + 1),
+            None => 1.0,
+        }
+    }
+}
+
+impl<C: Cost> Cost for RecencyCost<C> {
+    fn relative_cost_of(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> f64 {
+        self.relative_cost_counting_paths(main, dag, nodes).0
+    }
+
+    fn relative_cost_counting_paths(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> (f64, usize) {
+        self.observe(dag);
+
+        let (cost, paths) = self.base.relative_cost_counting_paths(main, dag, nodes);
+        (cost * self.discount(nodes), paths)
+    }
+
+    fn edge_contributions(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> Vec<EdgeContribution> {
+        self.observe(dag);
+
+        let discount = self.discount(nodes);
+        self.base
+            .edge_contributions(main, dag, nodes)
+            .into_iter()
+            .map(|(a, b, cost)| (a, b, cost * discount))
+            .collect()
+    }
+
+    fn rejected(&mut self, dag: &PartialDag<()>, nodes: &[Either<usize, SuperNode>]) {
+        self.base.rejected(dag, nodes)
+    }
+}
+
+/// Gives the edges of candidate paths which keep losing out (see `Cost::rejected`) a growing bonus
+/// over the `base` cost, so that the grower eventually tries them.
+///
+/// The `n`th rejection of an edge takes `bonus * decay^(n - 1)` off of its cost whenever a path would add it
+/// to the DAG, so, for a `decay` below 1, an edge's total bonus is capped at `bonus / (1 - decay)`.
+/// Only edges which were not yet in the DAG when their path was rejected are counted.
+#[derive(Debug, Default, Clone)]
+pub struct AdaptiveCost<C: Cost> {
+    pub base: C,
+    pub bonus: f64,
+    pub decay: f64,
+    rejections: HashMap<Edge, usize>,
+}
+
+impl<C: Cost> AdaptiveCost<C> {
+    pub fn new(base: C, bonus: f64, decay: f64) -> Self {
+        Self { base, bonus, decay, rejections: HashMap::new() }
+    }
+
+    /// How many times the edge `a -> b` was part of a rejected path.
+    pub fn rejections(&self, a: Either<usize, SuperNode>, b: Either<usize, SuperNode>) -> usize {
+        self.rejections.get(&(a, b)).copied().unwrap_or(0)
+    }
+
+    /// The total bonus taken off of the edge `a -> b`.
+    fn edge_bonus(&self, a: Either<usize, SuperNode>, b: Either<usize, SuperNode>) -> f64 {
+        (0..self.rejections(a, b)).map(|n| self.bonus * self.decay.powi(n as i32)).sum()
+    }
+}
+
+impl<C: Cost> Cost for AdaptiveCost<C> {
+    fn relative_cost_of(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> f64 {
+        self.relative_cost_counting_paths(main, dag, nodes).0
+    }
+
+    fn relative_cost_counting_paths(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> (f64, usize) {
+        let bonus = new_edges(dag, nodes).map(|(a, b)| self.edge_bonus(a, b)).sum::<f64>();
+
+        let (cost, paths) = self.base.relative_cost_counting_paths(main, dag, nodes);
+        (cost - bonus, paths)
+    }
+
+    fn edge_contributions(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> Vec<EdgeContribution> {
+        self.base
+            .edge_contributions(main, dag, nodes)
+            .into_iter()
+            .map(|(a, b, cost)| (a, b, cost - self.edge_bonus(a, b)))
+            .collect()
+    }
+
+    fn rejected(&mut self, dag: &PartialDag<()>, nodes: &[Either<usize, SuperNode>]) {
+        for edge in new_edges(dag, nodes) {
+            *self.rejections.entry(edge).or_default() += 1;
+        }
+
+        self.base.rejected(dag, nodes)
+    }
+}
+
+/// A cost over several objectives, which are all minimized. Rather than being ranked,
+/// candidates are filtered down to their `pareto_front`.
+pub trait MultiCost {
+    /// Like `Cost::relative_cost_of`, but with one relative cost per objective.
+    /// Every call should return the same number of objectives.
+    fn objectives(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> Vec<f64>;
+}
+
+/// Adapts a scalar `Cost` into a single-objective `MultiCost`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SingleObjective<C: Cost>(pub C);
+
+impl<C: Cost> MultiCost for SingleObjective<C> {
+    fn objectives(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> Vec<f64> {
+        vec![self.0.relative_cost_of(main, dag, nodes)]
+    }
+}
+
+/// The `EdgeCost` of a path, alongside the number of edges it adds to the DAG.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EdgeAndLengthCost;
+
+impl MultiCost for EdgeAndLengthCost {
+    fn objectives(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> Vec<f64> {
+        vec![
+            EdgeCost.relative_cost_of(main, dag, nodes),
+            new_edge_count(dag, nodes) as f64,
+        ]
+    }
+}
+
+/// Filters `candidates` down to those which are not dominated by another candidate: that is, there is no
+/// other candidate which is at least as good in every objective and strictly better in one.
+/// The remaining candidates keep their relative order.
+pub fn pareto_front<T>(candidates: Vec<(Vec<f64>, T)>) -> Vec<(Vec<f64>, T)> {
+    let dominates = |a: &[f64], b: &[f64]| a.iter().zip(b).all(|(a, b)| a <= b) && a.iter().zip(b).any(|(a, b)| a < b);
+
+    let dominated = candidates
+        .iter()
+        .map(|(objectives, _)| candidates.iter().any(|(other, _)| dominates(other, objectives)))
+        .collect::<Vec<_>>();
+
+    candidates
+        .into_iter()
+        .zip(dominated)
+        .filter(|(_, dominated)| !dominated)
+        .map(|(candidate, _)| candidate)
+        .collect()
+}
+
+impl<C: Cost + ?Sized> Cost for Box<C> {
+    fn relative_cost_of(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> f64 {
+        (**self).relative_cost_of(main, dag, nodes)
+    }
+
+    fn relative_cost_counting_paths(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> (f64, usize) {
+        (**self).relative_cost_counting_paths(main, dag, nodes)
+    }
+
+    fn edge_contributions(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> Vec<EdgeContribution> {
+        (**self).edge_contributions(main, dag, nodes)
+    }
+
+    fn rejected(&mut self, dag: &PartialDag<()>, nodes: &[Either<usize, SuperNode>]) {
+        (**self).rejected(dag, nodes)
+    }
+}
+
+/// Produces a fresh cost. See `CostRegistry`.
+pub type CostFactory = Arc<dyn Fn() -> Box<dyn Cost + Send + Sync> + Send + Sync>;
+
+/// Maps names to cost functions, so that costs (including those outside of this crate) can be chosen by name.
+#[derive(Clone)]
+pub struct CostRegistry {
+    factories: BTreeMap<String, CostFactory>,
+}
+
+impl Default for CostRegistry {
+    /// A registry with the built-in `edge`, `path`, and `node` costs.
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register("edge", || Box::new(EdgeCost));
+        registry.register("path", || Box::new(PathCost));
+        registry.register("node", || Box::new(NodeCost));
+        registry
+    }
+}
+
+impl CostRegistry {
+    /// A registry without any costs, not even the built-in ones.
+    pub fn empty() -> Self {
+        Self { factories: BTreeMap::new() }
+    }
+
+    /// Registers a cost under `name`, replacing any cost already registered under it.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        factory: impl Fn() -> Box<dyn Cost + Send + Sync> + Send + Sync + 'static,
+    ) {
+        self.factories.insert(name.into(), Arc::new(factory));
+    }
+
+    /// Creates the cost registered under `name`.
+    pub fn resolve(&self, name: &str) -> Option<RegisteredCost> {
+        self.factories.get(name).map(|factory| RegisteredCost {
+            factory: factory.clone(),
+            cost: factory(),
+        })
+    }
+
+    /// The names of every registered cost, in sorted order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.factories.keys().map(String::as_str)
+    }
+}
+
+/// A cost created by a `CostRegistry`. Cloning it creates a fresh cost from the same factory.
+pub struct RegisteredCost {
+    factory: CostFactory,
+    cost: Box<dyn Cost + Send + Sync>,
+}
+
+impl Clone for RegisteredCost {
+    fn clone(&self) -> Self {
+        Self {
+            factory: self.factory.clone(),
+            cost: (self.factory)(),
+        }
+    }
+}
+
+impl Cost for RegisteredCost {
+    fn relative_cost_of(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> f64 {
+        self.cost.relative_cost_of(main, dag, nodes)
+    }
+
+    fn relative_cost_counting_paths(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> (f64, usize) {
+        self.cost.relative_cost_counting_paths(main, dag, nodes)
+    }
+
+    fn edge_contributions(
+        &mut self,
+        main: &Interactome<Weight>,
+        dag: &PartialDag<()>,
+        nodes: &[Either<usize, SuperNode>],
+    ) -> Vec<EdgeContribution> {
+        self.cost.edge_contributions(main, dag, nodes)
+    }
+
+    fn rejected(&mut self, dag: &PartialDag<()>, nodes: &[Either<usize, SuperNode>]) {
+        self.cost.rejected(dag, nodes)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::parsing::{
-        data::EmptyTupleDataFactory, network::Network, weight::WeightDataFactory,
+    use crate::{
+        alg::grow::{grow_k, produce_dag, produce_dag_pareto, GrowthCache},
+        parsing::{data::EmptyTupleDataFactory, network::Network, weight::WeightDataFactory},
     };
 
     use super::*;
@@ -138,6 +738,7 @@ mod tests {
             let dag_network = Network::from_lines_using_id_map::<EmptyTupleDataFactory, _>(
                 vec![Ok("A\tB".to_string()), Ok("B\tC".to_string())].into_iter(),
                 &main_network_id_map,
+                false,
             )
             .unwrap();
 
@@ -163,4 +764,208 @@ mod tests {
         );
         assert_eq!(cost, 1.2);
     }
+
+    /// Builds an interactome from `lines` with a source A and target D, along with the DAG A -> M -> D.
+    fn amd_fixture(lines: &[&str], node_weights: &[(&str, f64)]) -> (Interactome<Weight>, PartialDag<()>) {
+        let mut main_network =
+            Network::from_lines::<WeightDataFactory, _>(lines.iter().map(|line| Ok(line.to_string()))).unwrap();
+        for &(node, weight) in node_weights {
+            let node = main_network.get_node(node).unwrap();
+            main_network.node_weights.insert(node, weight);
+        }
+
+        let id_map = main_network.id_map.clone();
+        let (sources, targets) = (["A".to_string()], ["D".to_string()]);
+        let interactome = Interactome::attach_sources_and_targets(main_network, &sources, &targets, true).unwrap();
+        let dag = PartialDag::new(
+            Network::from_lines_using_id_map::<EmptyTupleDataFactory, _>(
+                [Ok("A\tM".to_string()), Ok("M\tD".to_string())].into_iter(),
+                &id_map,
+                false,
+            )
+            .unwrap(),
+            &sources,
+            &targets,
+        )
+        .unwrap();
+
+        (interactome, dag)
+    }
+
+    #[test]
+    fn path_cost_counts_enumerated_paths() {
+        let (interactome, dag) = amd_fixture(&["A\tM\t1.0", "M\tD\t1.0", "A\tB\t0.5", "B\tD\t0.5", "B\tM\t0.5"], &[]);
+        let [a, b, d, m] = interactome.inner_network.as_nodes(&["A", "B", "D", "M"]).unwrap()[..] else {
+            unreachable!()
+        };
+        // A -> B -> D makes a diamond with A -> M -> D: two paths.
+        assert_eq!(PathCost.relative_cost_counting_paths(&interactome, &dag, &[a, b, d]), (2.0 + 1.0, 2));
+
+        // A -> B -> M adds A -> B -> M -> D alongside A -> M -> D.
+        assert_eq!(PathCost.relative_cost_counting_paths(&interactome, &dag, &[a, b, m]).1, 2);
+
+        // Wrappers, even boxed ones, pass the count through.
+        let mut boxed: Box<dyn Cost> = Box::new(LengthPenaltyCost::new(PathCost, 0.5));
+        assert_eq!(boxed.relative_cost_counting_paths(&interactome, &dag, &[a, b, d]), (2.0 + 1.0 + 1.0, 2));
+        assert_eq!(EdgeCost.relative_cost_counting_paths(&interactome, &dag, &[a, b, d]).1, 0);
+    }
+
+    /// The name of the second node on the path chosen under `cost`.
+    fn first_hop(interactome: &Interactome<Weight>, dag: &PartialDag<()>, cost: &mut impl Cost) -> String {
+        let mut cache = GrowthCache::new(interactome.clone());
+        let (_, path) = produce_dag(interactome, dag, &mut cache, cost).unwrap().unwrap();
+        interactome.inner_network.id_from_idx(path[1].left().unwrap()).cloned().unwrap()
+    }
+
+    #[test]
+    fn combined_cost_alpha() {
+        // Against the DAG A -> M -> D, A -> B -> D has the cheapest edges,
+        // but B is an expensive node, unlike C in A -> C -> M.
+        let (interactome, dag) = amd_fixture(
+            &["A\tM\t1.0", "M\tD\t1.0", "A\tB\t0.1", "B\tD\t0.1", "A\tC\t0.5", "C\tM\t0.5"],
+            &[("B", 10.0)],
+        );
+
+        assert_eq!(first_hop(&interactome, &dag, &mut CombinedCost::new(1.0)), "B");
+        assert_eq!(first_hop(&interactome, &dag, &mut CombinedCost::new(0.0)), "C");
+    }
+
+    #[test]
+    fn length_penalty() {
+        // Against the DAG A -> M -> D, A -> B -> C -> D has the cheapest edges,
+        // but is a hop longer than A -> E -> M.
+        let (interactome, dag) = amd_fixture(
+            &["A\tM\t1.0", "M\tD\t1.0", "A\tB\t0.1", "B\tC\t0.1", "C\tD\t0.1", "A\tE\t0.25", "E\tM\t0.25"],
+            &[],
+        );
+
+        assert_eq!(first_hop(&interactome, &dag, &mut LengthPenaltyCost::new(EdgeCost, 0.0)), "B");
+        assert_eq!(first_hop(&interactome, &dag, &mut LengthPenaltyCost::new(EdgeCost, 1.0)), "E");
+    }
+
+    #[test]
+    fn recency_clusters_growth() {
+        // Against the DAG A -> M -> D, A -> X -> D is grown first. Afterwards, A -> Z -> M has cheaper edges,
+        // but X -> Y -> D builds on X.
+        let (interactome, dag) = amd_fixture(
+            &[
+                "A\tM\t1.0", "M\tD\t1.0", "A\tX\t0.1", "X\tD\t0.1", "X\tY\t0.5", "Y\tD\t0.5", "A\tZ\t0.4",
+                "Z\tM\t0.4",
+            ],
+            &[],
+        );
+
+        let hops = |mut cost: RecencyCost<EdgeCost>| {
+            let (mut dag, mut cache) = (dag.clone(), GrowthCache::new(interactome.clone()));
+            grow_k(&interactome, &mut dag, &mut cache, &mut cost, 2, None)
+                .unwrap()
+                .into_iter()
+                .map(|(_, path)| interactome.inner_network.id_from_idx(path[1].left().unwrap()).cloned().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(hops(RecencyCost::new(EdgeCost, 0.0)), vec!["X", "Z"]);
+        assert_eq!(hops(RecencyCost::new(EdgeCost, 0.9)), vec!["X", "Y"]);
+    }
+
+    #[test]
+    fn adaptive_cost_tries_rejected_edges() {
+        // Against the DAG A -> M -> D, A -> P_i -> D is always cheaper than M -> Q -> D, which is rejected
+        // every iteration, while each A -> P_i -> D only becomes a candidate once the previous one is grown.
+        let (interactome, dag) = amd_fixture(
+            &[
+                "A\tM\t1.0", "M\tD\t1.0", "A\tP1\t0.1", "P1\tD\t0.1", "A\tP2\t0.1", "P2\tD\t0.1", "A\tP3\t0.1",
+                "P3\tD\t0.1", "A\tP4\t0.1", "P4\tD\t0.1", "M\tQ\t0.15", "Q\tD\t0.15",
+            ],
+            &[],
+        );
+
+        // The (zero-based) iteration which grows Q.
+        fn q_iteration(interactome: &Interactome<Weight>, dag: &PartialDag<()>, cost: &mut impl Cost) -> usize {
+            let (mut dag, mut cache) = (dag.clone(), GrowthCache::new(interactome.clone()));
+            let q = Either::Left(interactome.inner_network.get_node("Q").unwrap());
+            grow_k(interactome, &mut dag, &mut cache, cost, 5, None)
+                .unwrap()
+                .into_iter()
+                .position(|(_, path)| path.contains(&q))
+                .unwrap()
+        }
+
+        assert_eq!(q_iteration(&interactome, &dag, &mut EdgeCost), 4);
+
+        let mut adaptive = AdaptiveCost::new(EdgeCost, 0.03, 0.9);
+        assert_eq!(q_iteration(&interactome, &dag, &mut adaptive), 2);
+        let (m, q) = (
+            Either::Left(interactome.inner_network.get_node("M").unwrap()),
+            Either::Left(interactome.inner_network.get_node("Q").unwrap()),
+        );
+        assert_eq!(adaptive.rejections(m, q), 2);
+    }
+
+    #[test]
+    fn pareto_front_keeps_tradeoffs() {
+        // A -> B -> C -> D has the cheapest edges, while A -> E -> M is shorter, so neither dominates.
+        let (interactome, dag) = amd_fixture(
+            &["A\tM\t1.0", "M\tD\t1.0", "A\tB\t0.1", "B\tC\t0.1", "C\tD\t0.1", "A\tE\t0.25", "E\tM\t0.25"],
+            &[],
+        );
+
+        let mut cache = GrowthCache::new(interactome.clone());
+        let mut front = produce_dag_pareto(&interactome, &dag, &mut cache, &mut EdgeAndLengthCost)
+            .unwrap()
+            .into_iter()
+            .map(|(objectives, path)| {
+                let first_hop = interactome.inner_network.id_from_idx(path[1].left().unwrap()).cloned().unwrap();
+                (objectives, first_hop)
+            })
+            .collect::<Vec<_>>();
+        front.sort_by(|(_, a), (_, b)| a.cmp(b));
+
+        assert_eq!(front.len(), 2);
+        assert_eq!(front[0].1, "B");
+        assert_eq!(front[0].0[1], 3.0);
+        assert_eq!(front[1], (vec![0.5, 2.0], "E".to_string()));
+
+        // Equal candidates don't dominate each other.
+        assert_eq!(
+            pareto_front(vec![(vec![1.0, 1.0], 'a'), (vec![1.0, 2.0], 'b'), (vec![1.0, 1.0], 'c')]),
+            vec![(vec![1.0, 1.0], 'a'), (vec![1.0, 1.0], 'c')]
+        );
+    }
+
+    #[test]
+    fn equal_costs_prefer_shorter_paths() {
+        // A -> B -> C -> M and A -> E -> D both cost 3, but the latter is a hop shorter
+        // (despite being generated second, as M has a smaller id than D).
+        let (interactome, dag) = amd_fixture(
+            &["A\tM\t1.0", "M\tD\t1.0", "A\tB\t1.0", "B\tC\t1.0", "C\tM\t1.0", "A\tE\t1.5", "E\tD\t1.5"],
+            &[],
+        );
+
+        assert_eq!(first_hop(&interactome, &dag, &mut EdgeCost), "E");
+    }
+
+    #[test]
+    fn cost_registry() {
+        /// Every path costs the same.
+        struct ConstantCost;
+        impl Cost for ConstantCost {
+            fn relative_cost_of(&mut self, _: &Interactome<Weight>, _: &PartialDag<()>, _: &[Either<usize, SuperNode>]) -> f64 {
+                42.0
+            }
+        }
+
+        let mut registry = CostRegistry::default();
+        registry.register("constant", || Box::new(ConstantCost));
+
+        assert_eq!(registry.names().collect::<Vec<_>>(), vec!["constant", "edge", "node", "path"]);
+        assert!(registry.resolve("missing").is_none());
+
+        let (interactome, dag) = amd_fixture(&["A\tM\t1.0", "M\tD\t1.0", "A\tB\t0.1", "B\tD\t0.1"], &[]);
+        let path = interactome.inner_network.as_nodes(&["A", "B", "D"]).unwrap();
+        let mut cost = registry.resolve("constant").unwrap();
+        assert_eq!(cost.relative_cost_of(&interactome, &dag, &path), 42.0);
+        assert_eq!(cost.clone().relative_cost_of(&interactome, &dag, &path), 42.0);
+        assert!((registry.resolve("edge").unwrap().relative_cost_of(&interactome, &dag, &path) - 0.2).abs() < 1e-12);
+    }
 }
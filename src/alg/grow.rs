@@ -1,31 +1,376 @@
-use std::collections::HashMap;
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    hash::BuildHasher,
+    iter,
+};
 
 use either::Either;
-use petgraph::{algo::toposort, visit::IntoEdgeReferences};
+use petgraph::{
+    algo::toposort,
+    graphmap::DiGraphMap,
+    visit::{Dfs, IntoEdgeReferences, Reversed},
+};
+use rayon::{prelude::*, ThreadPool};
+use thiserror::Error;
+use xxhash_rust::xxh3::Xxh3Builder;
 
 use crate::{
-    alg::path::calculate_paths,
+    alg::path::{
+        bellman_ford_paths, calculate_paths_in, calculate_paths_scaled_in, reconstruct_path, single_source_distances,
+        DijkstraScratch, NegativeCycleError,
+    },
     parsing::{
         dag::PartialDag,
         interactome::{Interactome, SuperNode},
         network::{Network, NetworkIndexError},
-        weight::Weight,
+        weight::{EdgeTypes, Weight},
     },
     util::get_ancestors,
 };
 
-use super::{cost::Cost, path::Paths};
+use super::{
+    cost::{pareto_front, Cost, MultiCost},
+    path::Paths,
+};
+
+#[derive(Debug, Error)]
+pub enum GrowError {
+    #[error(transparent)]
+    NetworkIndexError(#[from] NetworkIndexError),
+    #[error("The candidate graph has a negative-weight cycle through {0}.")]
+    NegativeCycle(String),
+    #[error("The interactome has a negative weight on {0}, under which Dijkstra may not find the shortest paths.")]
+    NegativeWeight(String),
+}
+
+/// A grown path along with its relative cost.
+pub type ScoredPath = (f64, Vec<Either<usize, SuperNode>>);
+
+/// A grown path along with its relative cost in each objective of a `MultiCost`.
+pub type MultiScoredPath = (Vec<f64>, Vec<Either<usize, SuperNode>>);
+
+/// How much work `produce_dag_with_diagnostics` did, which explains why some iterations are slower than others.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GrowDiagnostics {
+    /// The number of nodes in the candidate graph, after the DAG's edges are removed.
+    pub candidate_nodes: usize,
+    /// The number of edges in the candidate graph, after the DAG's edges are removed.
+    pub candidate_edges: usize,
+    /// The total number of edge relaxations across every path search.
+    pub relaxations: usize,
+    /// The number of DAG nodes whose path search was skipped, as none of their neighbors can reach the DAG.
+    pub dead_ends: usize,
+    /// The number of source-target paths the cost enumerated to score every candidate, which is only ever nonzero
+    /// for costs such as `PathCost` (see `Cost::relative_cost_counting_paths`).
+    pub paths_enumerated: usize,
+    /// How much cheaper the chosen path is than the cheapest route the DAG already has between its endpoints,
+    /// both by interactome weight (see `route_improvement`). `None` if no path was chosen.
+    pub improvement: Option<f64>,
+}
+
+/// Options which change how `produce_dag` selects paths.
+#[derive(Clone, Debug, Default)]
+pub struct GrowthOptions {
+    /// When set, equal-cost, equal-length candidates are ordered pseudo-randomly by this seed,
+    /// rather than by their node ids.
+    pub tie_break_seed: Option<u64>,
+    /// When set, path-finding sums weights as integers scaled by `10^precision` (see `calculate_paths_scaled`),
+    /// so that equal-cost paths compare equal regardless of floating-point rounding.
+    pub scaled_precision: Option<i32>,
+    /// When set, only paths which grow toward one of these targets (by id) are candidates:
+    /// that is, paths ending at a target or at a DAG node which leads to it. Targets which aren't in the DAG yet
+    /// are grown toward by paths into the super-target through them.
+    pub target_focus: Option<Vec<usize>>,
+    /// When set, `grow_k` focuses each iteration on the next of the interactome's targets in turn
+    /// (see `Self::target_focus`), so that hard-to-reach targets aren't starved by cheaper ones.
+    pub rotate_targets: bool,
+    /// Candidates whose costs differ by at most this much are treated as tied, and ordered by the tie-break
+    /// (see `produce_dag_candidates`) instead. The tolerance is absolute for costs below 1, and relative above,
+    /// so that floating-point accumulation noise doesn't decide which path wins. Defaults to 0, comparing costs exactly.
+    pub cost_epsilon: f64,
+    /// When set, nodes left without any edges once the DAG's edges are removed from the candidate graph stay in it,
+    /// rather than being removed. Either way, no path can pass through such a node in that iteration, and since
+    /// the candidate graph is copied afresh for every iteration, removal never hides a node from later ones:
+    /// this only changes `GrowDiagnostics::candidate_nodes`, and whether DAG nodes without edges are searched.
+    pub keep_empty_nodes: bool,
+    /// When `grow_k` stops, besides running out of iterations or paths.
+    pub until: StopCondition,
+    /// How equal-cost candidates are ordered, before falling back to their length (see `produce_dag_candidates`).
+    pub tie_break: TieBreak,
+    /// When set, the ancestors of every DAG node are kept in the `GrowthCache` and updated as paths are grown,
+    /// rather than being searched for afresh by every `produce_dag` call. This picks the same paths either way.
+    pub cache_ancestors: bool,
+    /// When set, paths which would take the DAG past this many edges between genes (see
+    /// `PartialDag::interaction_count`) are refused, which stops growth (see `GrowthCache::max_edges_reached`).
+    pub max_edges: Option<usize>,
+    /// When set, an edge added through `GrowthCache::add_interactome_edge` between genes which already have an edge
+    /// keeps the smaller of the two weights, rather than overwriting it, so that path-finding always sees the
+    /// cheapest of parallel edges. This only affects edges added after it is set.
+    pub merge_parallel_edges: bool,
+    /// When set, every edge of a grown path is removed from the cache's candidate graph for good, so no later
+    /// path can use it, even one grown into another DAG sharing this cache. Paths grown into the same DAG never
+    /// share edges anyway, as the DAG's edges are left out of each iteration's candidate graph.
+    pub edge_disjoint: bool,
+    /// When set, paths may only use edges of the allowed interaction types: the others are removed from the cache's
+    /// candidate graph the first time it grows with this set. Untyped edges, such as those to and from super-nodes,
+    /// are always kept. Changing the filter afterwards needs a `GrowthCache::reset` to take effect.
+    pub edge_types: Option<EdgeTypeFilter>,
+}
+
+/// The interaction types growth may traverse. See `GrowthOptions::edge_types`.
+#[derive(Clone, Debug, Default)]
+pub struct EdgeTypeFilter {
+    /// The type of every typed edge, as split off by `Network::split_edge_types`.
+    pub types: EdgeTypes,
+    /// The types which paths may use.
+    pub allowed: HashSet<String>,
+}
+
+/// How equal-cost candidates are first ordered. See `GrowthOptions::tie_break`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Go straight to the length and node ids.
+    #[default]
+    Length,
+    /// Prefer the candidate whose most expensive edge not yet in the DAG (by interactome weight) is cheapest,
+    /// so that, of two paths with the same total cost, the more balanced one wins over one with a weak link.
+    Bottleneck,
+}
+
+/// When growth stops, besides running out of iterations or paths. See `GrowthOptions::until`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StopCondition {
+    /// Only stop once every iteration has run.
+    #[default]
+    Iterations,
+    /// Stop once every one of the interactome's targets is connected to the super-source in the DAG.
+    AllTargetsConnected,
+}
+
+impl StopCondition {
+    /// Whether growing `dag` against `interactome` should stop.
+    pub fn is_met(self, interactome: &Interactome<Weight>, dag: &PartialDag<()>) -> bool {
+        match self {
+            StopCondition::Iterations => false,
+            StopCondition::AllTargetsConnected => dag.connects_targets(&interactome.targets),
+        }
+    }
+}
 
+/// Persistent state across growth iterations. The candidate graph is left untouched
+/// by `produce_dag`, which works over its own copy, so one cache can be reused
+/// across every iteration of a growth run.
 pub struct GrowthCache {
-    candidate: Network<Weight, SuperNode>,
+    pub options: GrowthOptions,
+    /// `None` until a lazy cache (see `Self::lazy`) first grows.
+    candidate: Option<Network<Weight, SuperNode>>,
+    /// Edges added through `Self::add_interactome_edge`.
+    added_edges: Vec<(usize, usize, Weight)>,
+    /// Edges added through `Self::add_interactome_edge` before the candidate graph was built.
+    pending_edges: Vec<(String, String, Weight)>,
+    /// Path-finding buffers, cleared and reused by every `produce_dag` call rather than reallocated.
+    paths: Paths<Either<usize, SuperNode>>,
+    scratch: DijkstraScratch<Either<usize, SuperNode>>,
+    scaled_scratch: DijkstraScratch<Either<usize, SuperNode>, i64>,
+    /// With `GrowthOptions::cache_ancestors`, the ancestors of the DAG last grown. `None` until first needed.
+    ancestors: Option<AncestorCache>,
+    /// Whether the last path written was refused for exceeding `GrowthOptions::max_edges`.
+    max_edges_reached: bool,
+    /// Whether `GrowthOptions::edge_types` was applied to the candidate graph.
+    edge_types_applied: bool,
+}
+
+/// The ancestors of every node of a DAG, kept up to date as edges are added by `Self::add_edge`.
+/// Ancestors are sorted, so that they are removed from the candidate graph in the same order however they were found.
+#[derive(Clone, Debug)]
+struct AncestorCache {
+    ancestors: HashMap<Either<usize, SuperNode>, BTreeSet<Either<usize, SuperNode>>>,
+    /// The fingerprint of the DAG these ancestors are for (see `Self::fingerprint`), so that a cache shared
+    /// across DAGs, or a DAG changed behind the cache's back, is rebuilt rather than trusted.
+    fingerprint: (usize, usize, u64),
+}
+
+impl AncestorCache {
+    fn new(dag: &PartialDag<()>) -> Self {
+        let graph = &dag.0.inner_network.graph;
+        Self {
+            ancestors: graph.nodes().map(|node| (node, get_ancestors(graph, node).into_iter().collect())).collect(),
+            fingerprint: Self::fingerprint(dag),
+        }
+    }
+
+    /// The DAG's node and edge counts, along with a hash over its nodes and edges which doesn't depend on their order.
+    fn fingerprint(dag: &PartialDag<()>) -> (usize, usize, u64) {
+        let graph = &dag.0.inner_network.graph;
+        let hasher = Xxh3Builder::new();
+        let nodes = graph.nodes().fold(0, |hash, node| hash ^ hasher.hash_one(node));
+        let edges = graph.all_edges().fold(0, |hash, (a, b, _)| hash ^ hasher.hash_one((a, b)));
+
+        (graph.node_count(), graph.edge_count(), nodes ^ edges.rotate_left(1))
+    }
+
+    fn is_current(&self, dag: &PartialDag<()>) -> bool {
+        self.fingerprint == Self::fingerprint(dag)
+    }
+
+    /// Records the edge `a -> b`: `a` and its ancestors become ancestors of `b` and of everything after it.
+    fn add_edge(&mut self, a: Either<usize, SuperNode>, b: Either<usize, SuperNode>) {
+        let mut added = self.ancestors.entry(a).or_default().clone();
+        added.insert(a);
+
+        self.ancestors.entry(b).or_default();
+        for (&node, ancestors) in &mut self.ancestors {
+            if node == b || ancestors.contains(&b) {
+                ancestors.extend(&added);
+            }
+        }
+    }
 }
 
 impl GrowthCache {
+    /// A cache whose candidate graph is `interactome`, which must be the interactome later passed to `produce_dag`.
     pub fn new(interactome: Interactome<Weight>) -> Self {
+        Self { candidate: Some(interactome.inner_network), ..Self::lazy() }
+    }
+
+    /// A cache which copies its candidate graph from the interactome passed to the first `produce_dag` call,
+    /// rather than taking one upfront. This makes creating a cache which may never grow free.
+    pub fn lazy() -> Self {
         Self {
-            candidate: interactome.inner_network,
+            options: GrowthOptions::default(),
+            candidate: None,
+            added_edges: vec![],
+            pending_edges: vec![],
+            paths: Paths::new(),
+            scratch: DijkstraScratch::default(),
+            scaled_scratch: DijkstraScratch::default(),
+            ancestors: None,
+            max_edges_reached: false,
+            edge_types_applied: false,
+        }
+    }
+
+    /// Restores the candidate graph to `interactome` exactly, discarding every added edge (see
+    /// `Self::add_interactome_edge`) and every edge removed by `GrowthOptions::edge_disjoint`, so that one cache can
+    /// run several independent growths. Options and path-finding buffers are kept.
+    pub fn reset(&mut self, interactome: &Interactome<Weight>) {
+        self.candidate = Some(interactome.inner_network.clone());
+        self.added_edges.clear();
+        self.pending_edges.clear();
+        self.ancestors = None;
+        self.max_edges_reached = false;
+        self.edge_types_applied = false;
+    }
+
+    /// Whether the last path grown was refused, as it would have taken the DAG past `GrowthOptions::max_edges`.
+    /// Since the same path would be picked again, growth should stop there.
+    pub fn max_edges_reached(&self) -> bool {
+        self.max_edges_reached
+    }
+
+    /// Adds an edge `a -> b` to the interactome, allocating ids for genes which are not yet in it.
+    /// The edge is inserted into the candidate graph, and is logically part of the main interactome
+    /// used for cost (see `Self::main_interactome`).
+    ///
+    /// Added edges take effect from the next growth iteration. An edge which already exists has its weight
+    /// overwritten, unless `GrowthOptions::merge_parallel_edges` is set.
+    pub fn add_interactome_edge(&mut self, a: &str, b: &str, weight: Weight) {
+        let Some(candidate) = &mut self.candidate else {
+            self.pending_edges.push((a.to_string(), b.to_string(), weight));
+            return;
+        };
+
+        let existing = match (candidate.get_node(a), candidate.get_node(b)) {
+            (Ok(a), Ok(b)) => candidate.graph.edge_weight(Either::Left(a), Either::Left(b)).copied(),
+            _ => None,
+        };
+        let weight = match existing {
+            Some(existing) if self.options.merge_parallel_edges && existing.0 < weight.0 => existing,
+            _ => weight,
+        };
+
+        let (a, b) = candidate.add_edge_by_name_allocating(a, b, weight);
+        self.added_edges.push((a, b, weight));
+    }
+
+    /// The interactome edges (including added ones) which are not yet in `dag`, by name and with their weight,
+    /// sorted by name: that is, the edges which growth can still add. Edges into or out of super-nodes are left out.
+    ///
+    /// This is empty for a lazy cache (see `Self::lazy`) which has not grown yet, as it has no candidate graph.
+    pub fn available_edges(&self, dag: &PartialDag<()>) -> Vec<(String, String, f64)> {
+        let Some(candidate) = &self.candidate else {
+            return vec![];
+        };
+
+        let mut edges = candidate
+            .graph
+            .all_edges()
+            .filter(|&(a, b, _)| !dag.0.inner_network.graph.contains_edge(a, b))
+            .filter_map(|(a, b, weight)| {
+                Some((candidate.id_from_idx(a.left()?)?.clone(), candidate.id_from_idx(b.left()?)?.clone(), weight.0))
+            })
+            .collect::<Vec<_>>();
+        edges.sort_by(|(a, b, _), (c, d, _)| (a, b).cmp(&(c, d)));
+
+        edges
+    }
+
+    /// With `GrowthOptions::cache_ancestors`, takes the cached ancestors out of the cache, (re)building them if they
+    /// are missing or are not for `dag`. They should be put back once done.
+    fn take_ancestors(&mut self, dag: &PartialDag<()>) -> Option<AncestorCache> {
+        if !self.options.cache_ancestors {
+            return None;
+        }
+
+        match self.ancestors.take() {
+            Some(ancestors) if ancestors.is_current(dag) => Some(ancestors),
+            _ => Some(AncestorCache::new(dag)),
         }
     }
+
+    /// The candidate graph, copied from `interactome` (along with any pending added edges) if it wasn't built yet,
+    /// and without the edges `GrowthOptions::edge_types` doesn't allow.
+    fn candidate(&mut self, interactome: &Interactome<Weight>) -> &Network<Weight, SuperNode> {
+        if self.candidate.is_none() {
+            self.candidate = Some(interactome.inner_network.clone());
+            for (a, b, weight) in std::mem::take(&mut self.pending_edges) {
+                self.add_interactome_edge(&a, &b, weight);
+            }
+        }
+
+        let candidate = self.candidate.as_mut().unwrap();
+        if let Some(filter) = self.options.edge_types.as_ref().filter(|_| !self.edge_types_applied) {
+            strip_disallowed_edges(candidate, filter);
+            self.edge_types_applied = true;
+        }
+
+        candidate
+    }
+
+    /// The main `interactome`, extended with any edges added through `Self::add_interactome_edge`.
+    /// This only clones `interactome` if edges were added.
+    pub fn main_interactome<'a>(&self, interactome: &'a Interactome<Weight>) -> Cow<'a, Interactome<Weight>> {
+        if self.added_edges.is_empty() {
+            return Cow::Borrowed(interactome);
+        }
+
+        let candidate = self.candidate.as_ref().expect("edges are only added to a built candidate graph");
+        let mut main = interactome.clone();
+        for &(a, b, weight) in &self.added_edges {
+            for node in [a, b] {
+                if !main.inner_network.id_map.contains_right(&node) {
+                    let name = candidate.id_from_idx(node).cloned().unwrap();
+                    main.inner_network.id_map.insert(name, node);
+                }
+            }
+
+            main.inner_network.graph.add_edge(Either::Left(a), Either::Left(b), weight);
+        }
+
+        Cow::Owned(main)
+    }
 }
 
 /// **The heart of Growing DAGs**.
@@ -36,33 +381,398 @@ pub fn produce_dag<C: Cost>(
     dag: &PartialDag<()>,
     cache: &mut GrowthCache,
     cost: &mut C,
-) -> Result<Option<(f64, Vec<Either<usize, SuperNode>>)>, NetworkIndexError> {
-    // Prepare the candidate graph by removing the current DAG's edges
+) -> Result<Option<ScoredPath>, GrowError> {
+    let candidates = produce_dag_candidates(interactome, dag, cache, cost)?;
+
+    Ok(pick_best(dag, cost, candidates))
+}
+
+/// Takes the cheapest of the ranked `candidates`, reporting the others to `cost` (see `Cost::rejected`).
+fn pick_best<C: Cost>(dag: &PartialDag<()>, cost: &mut C, candidates: Vec<ScoredPath>) -> Option<ScoredPath> {
+    let mut candidates = candidates.into_iter();
+    let best = candidates.next();
+    for (_, path) in candidates {
+        cost.rejected(dag, &path);
+    }
+
+    best
+}
+
+/// Like `produce_dag`, but returns every candidate path which was considered alongside its cost,
+/// sorted from cheapest to most expensive. Every candidate adds at least one new edge to the DAG,
+/// as candidates are searched for without the DAG's edges. Equal-cost candidates are ordered by:
+/// 1. with `TieBreak::Bottleneck`, their most expensive new edge, cheapest first;
+/// 2. their length, shortest first;
+/// 3. their node ids, lexicographically (or pseudo-randomly, if `GrowthOptions::tie_break_seed` is set).
+pub fn produce_dag_candidates<C: Cost>(
+    interactome: &Interactome<Weight>,
+    dag: &PartialDag<()>,
+    cache: &mut GrowthCache,
+    cost: &mut C,
+) -> Result<Vec<ScoredPath>, GrowError> {
+    produce_candidates(interactome, dag, cache, cost, None)
+}
+
+/// The former name of `produce_dag_candidates`, which it is identical to.
+#[deprecated(note = "renamed to `produce_dag_candidates`")]
+pub fn produce_dag_ranked<C: Cost>(
+    interactome: &Interactome<Weight>,
+    dag: &PartialDag<()>,
+    cache: &mut GrowthCache,
+    cost: &mut C,
+) -> Result<Vec<ScoredPath>, GrowError> {
+    produce_dag_candidates(interactome, dag, cache, cost)
+}
+
+/// Like `produce_dag`, but also reports how much work was done to find the path.
+pub fn produce_dag_with_diagnostics<C: Cost>(
+    interactome: &Interactome<Weight>,
+    dag: &PartialDag<()>,
+    cache: &mut GrowthCache,
+    cost: &mut C,
+) -> Result<(Option<ScoredPath>, GrowDiagnostics), GrowError> {
+    let mut diagnostics = GrowDiagnostics::default();
+    let candidates = produce_candidates(interactome, dag, cache, cost, Some(&mut diagnostics))?;
+    let path = pick_best(dag, cost, candidates);
+    diagnostics.improvement =
+        path.as_ref().map(|(_, path)| route_improvement(&cache.main_interactome(interactome), dag, path));
+
+    Ok((path, diagnostics))
+}
+
+/// The value `path` adds to `dag`: the weight of the cheapest route already in the DAG between the path's endpoints
+/// (only through edges which are in `interactome`), less the path's own weight. Improvements which aren't positive
+/// mean the DAG could already connect the endpoints at least as cheaply, and the improvement is infinite
+/// when the DAG can't connect them at all.
+pub fn route_improvement(
+    interactome: &Interactome<Weight>,
+    dag: &PartialDag<()>,
+    path: &[Either<usize, SuperNode>],
+) -> f64 {
+    let weight = |a, b| interactome.inner_network.graph.edge_weight(a, b).copied();
+    let path_weight = path.windows(2).filter_map(|edge| weight(edge[0], edge[1])).map(|weight| weight.0).sum::<f64>();
+
+    let (Some(&start), Some(&end)) = (path.first(), path.last()) else {
+        return f64::INFINITY;
+    };
+    let mut routes = DiGraphMap::<_, Weight, Xxh3Builder>::new();
+    for (a, b, _) in dag.0.inner_network.graph.all_edges() {
+        if let Some(weight) = weight(a, b) {
+            routes.add_edge(a, b, weight);
+        }
+    }
+    if !routes.contains_node(start) {
+        return f64::INFINITY;
+    }
+
+    match single_source_distances(&routes, start).get(&end) {
+        Some(existing) => existing - path_weight,
+        None => f64::INFINITY,
+    }
+}
+
+/// Collects and ranks every candidate path, filling in `diagnostics` if it is present.
+fn produce_candidates<C: Cost>(
+    interactome: &Interactome<Weight>,
+    dag: &PartialDag<()>,
+    cache: &mut GrowthCache,
+    cost: &mut C,
+    diagnostics: Option<&mut GrowDiagnostics>,
+) -> Result<Vec<ScoredPath>, GrowError> {
+    enter_span!(INFO, "produce_dag", dag_nodes = dag.0.inner_network.node_count());
+    let mut diagnostics = diagnostics;
+    let paths = candidate_paths(interactome, dag, cache, diagnostics.as_deref_mut())?;
+    let interactome = cache.main_interactome(interactome);
+
+    Ok(rank_paths(&interactome, dag, &cache.options, cost, paths, diagnostics))
+}
+
+/// Whether `path` has any edge which is not already in the DAG. Paths which don't would waste an iteration,
+/// but `candidate_paths` never finds them.
+fn adds_new_edge(dag: &PartialDag<()>, path: &[Either<usize, SuperNode>]) -> bool {
+    path.windows(2)
+        .any(|edge| !dag.0.inner_network.graph.contains_edge(edge[0], edge[1]))
+}
+
+/// Scores `paths`, sorting them from cheapest to most expensive, and counting the paths enumerated along the way
+/// into `diagnostics` if it is present.
+fn rank_paths<C: Cost>(
+    interactome: &Interactome<Weight>,
+    dag: &PartialDag<()>,
+    options: &GrowthOptions,
+    cost: &mut C,
+    paths: Vec<Vec<Either<usize, SuperNode>>>,
+    diagnostics: Option<&mut GrowDiagnostics>,
+) -> Vec<ScoredPath> {
+    let mut paths_enumerated = 0;
+    // Rank the paths given the cost function.
+    let ranked_paths = paths
+        .into_iter()
+        .map(|path| {
+            let (cost, enumerated) = cost.relative_cost_counting_paths(interactome, dag, &path);
+            paths_enumerated += enumerated;
+            (cost, path)
+        })
+        .collect::<Vec<_>>();
+    if let Some(diagnostics) = diagnostics {
+        diagnostics.paths_enumerated += paths_enumerated;
+    }
+
+    sort_ranked_paths(interactome, dag, options, ranked_paths)
+}
+
+/// Like `rank_paths`, but scores the paths across rayon's threads, each with its own clone of `cost`.
+///
+/// The first path is scored by `cost` itself, so that costs which track the DAG across calls
+/// (such as `RecencyCost`) are kept up to date, and their clones see the same state.
+fn rank_paths_parallel<C: Cost + Clone + Send + Sync>(
+    interactome: &Interactome<Weight>,
+    dag: &PartialDag<()>,
+    options: &GrowthOptions,
+    cost: &mut C,
+    paths: Vec<Vec<Either<usize, SuperNode>>>,
+    diagnostics: Option<&mut GrowDiagnostics>,
+) -> Vec<ScoredPath> {
+    let mut paths = paths.into_iter();
+    let Some(first) = paths.next() else {
+        return vec![];
+    };
+
+    let (first_cost, mut paths_enumerated) = cost.relative_cost_counting_paths(interactome, dag, &first);
+    let mut ranked_paths = vec![(first_cost, first)];
+    let rest = paths
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map_init(
+            || cost.clone(),
+            |cost, path| {
+                let (cost, enumerated) = cost.relative_cost_counting_paths(interactome, dag, &path);
+                (enumerated, (cost, path))
+            },
+        )
+        .collect::<Vec<_>>();
+    for (enumerated, ranked) in rest {
+        paths_enumerated += enumerated;
+        ranked_paths.push(ranked);
+    }
+    if let Some(diagnostics) = diagnostics {
+        diagnostics.paths_enumerated += paths_enumerated;
+    }
+
+    sort_ranked_paths(interactome, dag, options, ranked_paths)
+}
+
+/// Sorts scored paths from cheapest to most expensive, breaking ties as documented in `produce_dag_candidates`.
+fn sort_ranked_paths(
+    interactome: &Interactome<Weight>,
+    dag: &PartialDag<()>,
+    options: &GrowthOptions,
+    mut ranked_paths: Vec<ScoredPath>,
+) -> Vec<ScoredPath> {
+    // The weight of the most expensive edge along `path` which is not in the DAG.
+    let bottleneck = |path: &[Either<usize, SuperNode>]| {
+        path.windows(2)
+            .filter(|edge| !dag.0.inner_network.graph.contains_edge(edge[0], edge[1]))
+            .map(|edge| interactome.inner_network.graph.edge_weight(edge[0], edge[1]).map_or(0.0, |weight| weight.0))
+            .fold(f64::NEG_INFINITY, f64::max)
+    };
+
+    // Ties in cost are broken by preferring shorter paths (a simpler hypothesis),
+    // and then by the seeded hash of the path, or by the path's node ids when unseeded.
+    let hasher = options.tie_break_seed.map(|seed| Xxh3Builder::new().with_seed(seed));
+    let tie_break = |x_path: &Vec<Either<usize, SuperNode>>, y_path: &Vec<Either<usize, SuperNode>>| {
+        let strategy = match options.tie_break {
+            TieBreak::Length => Ordering::Equal,
+            TieBreak::Bottleneck => bottleneck(x_path).total_cmp(&bottleneck(y_path)),
+        };
+
+        strategy.then_with(|| x_path.len().cmp(&y_path.len())).then_with(|| match &hasher {
+            Some(hasher) => hasher.hash_one(x_path).cmp(&hasher.hash_one(y_path)),
+            None => x_path.cmp(y_path),
+        })
+    };
+    ranked_paths.sort_by(|(x, x_path), (y, y_path)| x.total_cmp(y).then_with(|| tie_break(x_path, y_path)));
+
+    // Comparing within epsilon isn't transitive, so instead of sorting by it, each run of costs within epsilon
+    // of the run's cheapest cost is re-sorted by the tie-break alone.
+    if options.cost_epsilon > 0.0 {
+        let mut start = 0;
+        while start < ranked_paths.len() {
+            let cheapest = ranked_paths[start].0;
+            let tolerance = options.cost_epsilon * cheapest.abs().max(1.0);
+            let len = ranked_paths[start..].partition_point(|(cost, _)| cost - cheapest <= tolerance);
+            ranked_paths[start..start + len].sort_by(|(_, x_path), (_, y_path)| tie_break(x_path, y_path));
+            start += len;
+        }
+    }
+
+    ranked_paths
+}
+
+/// Like `produce_dag_candidates`, but scores candidates over several objectives, returning only the candidates
+/// which no other candidate beats (or ties) in every objective. See `pareto_front`.
+pub fn produce_dag_pareto<C: MultiCost>(
+    interactome: &Interactome<Weight>,
+    dag: &PartialDag<()>,
+    cache: &mut GrowthCache,
+    cost: &mut C,
+) -> Result<Vec<MultiScoredPath>, GrowError> {
+    let paths = candidate_paths(interactome, dag, cache, None)?;
+    let interactome = cache.main_interactome(interactome);
+
+    Ok(pareto_front(
+        paths
+            .into_iter()
+            .map(|path| (cost.objectives(&interactome, dag, &path), path))
+            .collect(),
+    ))
+}
+
+/// Prepares the candidate graph by removing the current DAG's edges,
+/// along with the nodes they leave empty unless `keep_empty_nodes` is set (see `GrowthOptions::keep_empty_nodes`).
+fn strip_dag_edges(candidate: &mut Network<Weight, SuperNode>, dag: &PartialDag<()>, keep_empty_nodes: bool) {
     for (source_idx, target_idx, _) in dag.0.inner_network.graph.edge_references() {
-        cache.candidate.graph.remove_edge(source_idx, target_idx);
-        
+        candidate.graph.remove_edge(source_idx, target_idx);
+
+        if keep_empty_nodes {
+            continue;
+        }
+
         // Remove empty vertices along the edges, getting the induced edge graph
-        // of the candidate (except for vertices on the candidate who were already alone)
-        // TODO: is that okay?
-        if cache.candidate.is_node_empty(source_idx) {
-            cache.candidate.graph.remove_node(source_idx);
+        // of the candidate (except for vertices on the candidate who were already alone).
+        // An empty vertex can't be on any path, so this is only a pruning step.
+        if candidate.is_node_empty(source_idx) {
+            candidate.graph.remove_node(source_idx);
+        }
+        if candidate.is_node_empty(target_idx) {
+            candidate.graph.remove_node(target_idx);
+        }
+    }
+}
+
+/// Removes every edge whose type isn't allowed by `filter`.
+fn strip_disallowed_edges(candidate: &mut Network<Weight, SuperNode>, filter: &EdgeTypeFilter) {
+    for (&(a, b), edge_type) in &filter.types {
+        if !filter.allowed.contains(edge_type) {
+            candidate.graph.remove_edge(Either::Left(a), Either::Left(b));
+        }
+    }
+}
+
+/// For debugging: the candidate graph exactly as `produce_dag` searches it for paths starting at the DAG node `node`,
+/// after the DAG's edges and the ancestors of `node` (and of the DAG nodes searched before it) are removed.
+/// Returns `None` if `node` is not in the DAG, or if `cache` is lazy and hasn't grown yet.
+pub fn searched_candidate(
+    dag: &PartialDag<()>,
+    cache: &GrowthCache,
+    node: Either<usize, SuperNode>,
+) -> Option<Network<Weight, SuperNode>> {
+    if !dag.0.inner_network.graph.contains_node(node) {
+        return None;
+    }
+
+    let mut candidate = cache.candidate.clone()?;
+    strip_dag_edges(&mut candidate, dag, cache.options.keep_empty_nodes);
+    if let Some(filter) = cache.options.edge_types.as_ref().filter(|_| !cache.edge_types_applied) {
+        strip_disallowed_edges(&mut candidate, filter);
+    }
+
+    // This mirrors the walk in `candidate_paths`, stopping at `node`.
+    for node_id in toposort(&dag.0.inner_network.graph, None).unwrap() {
+        let searched = !dag
+            .0
+            .inner_network
+            .graph
+            .contains_edge(node_id, Either::Right(SuperNode::Target))
+            && candidate.graph.contains_node(node_id);
+
+        if searched {
+            for ancestor in sorted_ancestors(dag, node_id) {
+                candidate.graph.remove_node(ancestor);
+            }
         }
-        if cache.candidate.is_node_empty(target_idx) {
-            cache.candidate.graph.remove_node(target_idx);
+
+        if node_id == node {
+            break;
         }
     }
 
+    Some(candidate)
+}
+
+/// The ancestors of `node` in `dag`, sorted, as they would be found in an `AncestorCache`.
+fn sorted_ancestors(dag: &PartialDag<()>, node: Either<usize, SuperNode>) -> BTreeSet<Either<usize, SuperNode>> {
+    get_ancestors(&dag.0.inner_network.graph, node).into_iter().collect()
+}
+
+/// Generates every (unscored) candidate path, filling in `diagnostics` if it is present.
+fn candidate_paths(
+    interactome: &Interactome<Weight>,
+    dag: &PartialDag<()>,
+    cache: &mut GrowthCache,
+    mut diagnostics: Option<&mut GrowDiagnostics>,
+) -> Result<Vec<Vec<Either<usize, SuperNode>>>, GrowError> {
+    let mut candidate = cache.candidate(interactome).clone();
+    strip_dag_edges(&mut candidate, dag, cache.options.keep_empty_nodes);
+
+    if let Some(diagnostics) = diagnostics.as_deref_mut() {
+        diagnostics.candidate_nodes = candidate.node_count();
+        diagnostics.candidate_edges = candidate.edge_count();
+    }
+
+    // Dijkstra is only correct over non-negative weights. Otherwise, we fall back to the (much slower) Bellman-Ford.
+    let negative_weights = candidate.has_negative_weights();
+    if negative_weights {
+        log::warn!("The candidate graph has negative weights: falling back to Bellman-Ford.");
+    }
+
+    // With a target focus, paths may only end at focused targets, or at the DAG nodes which lead to them.
+    // Focused targets which the DAG hasn't reached yet can only be grown into on the way to the super-target,
+    // so paths may end there too, as long as they pass through one of them last.
+    let focus = cache.options.target_focus.as_ref().map(|focus| {
+        let graph = &dag.0.inner_network.graph;
+        let (reached, unreached): (HashSet<_>, HashSet<_>) =
+            focus.iter().map(|&target| Either::Left(target)).partition(|&target| graph.contains_node(target));
+
+        let mut ends = reached
+            .into_iter()
+            .flat_map(|target| iter::once(target).chain(get_ancestors(graph, target)))
+            .collect::<HashSet<_>>();
+        if !unreached.is_empty() {
+            ends.insert(Either::Right(SuperNode::Target));
+        }
+
+        (ends, unreached)
+    });
+
+    let ancestor_cache = cache.take_ancestors(dag);
+
     // Prepare our 'parents' dictionary of (source, target) <-> (cost, parent)
-    let mut paths_parents: Paths<Either<usize, SuperNode>> = HashMap::new();
-    let mut all_targets: HashMap<Either<usize, SuperNode>, Vec<Either<usize, SuperNode>>> = HashMap::new();
+    let mut paths_parents = std::mem::take(&mut cache.paths);
+    paths_parents.clear();
+    // This is ordered so that candidate paths are generated in a reproducible (sorted-by-id) order.
+    let mut all_targets: BTreeMap<Either<usize, SuperNode>, Vec<Either<usize, SuperNode>>> = BTreeMap::new();
 
     // Create a topological sorting of all of the current nodes
     let nodes = toposort(&dag.0.inner_network.graph, None).unwrap();
 
+    // The candidate nodes which can reach some DAG node. A DAG node with no neighbor in here can't reach any target,
+    // so there is no need to search from it. Removing ancestors below only shrinks this set, so it stays conservative.
+    let reaches_dag = {
+        let reversed = Reversed(&candidate.graph);
+        let mut dfs = Dfs::empty(reversed);
+        for &node in nodes.iter().filter(|&&node| candidate.graph.contains_node(node)) {
+            dfs.move_to(node);
+            while dfs.next(reversed).is_some() {}
+        }
+        dfs.discovered
+    };
+
     // Re-iterate over every single existing node in the DAG, preparing our distance cache for later cost-minimization.
     for (idx, node_id) in nodes.into_iter().enumerate() {
-        let node_name = dag.0.name_from_idx(node_id).unwrap();
+        let node_name = dag.0.display_name(node_id);
         log::trace!("On the DAG node {node_name}.");
+        enter_span!(DEBUG, "dijkstra", node = %node_name);
 
         if dag
             .0
@@ -75,39 +785,79 @@ pub fn produce_dag<C: Cost>(
             continue;
         }
 
-        if !cache.candidate.graph.contains_node(node_id) {
+        if !candidate.graph.contains_node(node_id) {
             log::debug!("Skipping {node_id:?} named {node_name} as it is not in the candidate graph.");
             continue;
         }
 
-        let ancestors = get_ancestors(&dag.0.inner_network.graph, node_id);
+        let ancestors = match &ancestor_cache {
+            Some(ancestor_cache) => match ancestor_cache.ancestors.get(&node_id) {
+                Some(ancestors) => ancestors.clone(),
+                None => sorted_ancestors(dag, node_id),
+            },
+            None => sorted_ancestors(dag, node_id),
+        };
 
         // Preprocess the candidate graph by removing all ancestors of the current node
         for ancestor in &ancestors {
-            cache.candidate.graph.remove_node(*ancestor);
+            candidate.graph.remove_node(*ancestor);
             log::trace!("Removing ancestor {ancestor:?}");
         }
 
+        if !candidate.graph.neighbors(node_id).any(|next| reaches_dag.contains(&next)) {
+            log::debug!("Skipping {node_name}, as it can't reach any other DAG node.");
+            if let Some(diagnostics) = diagnostics.as_deref_mut() {
+                diagnostics.dead_ends += 1;
+            }
+            continue;
+        }
+
         // targets are the incomparable elements and the descendents of the DAG.
         // first, collect only the nodes which are not the ancestors or are not the current node
-        let targets = dag
+        let mut targets = dag
             .0
             .inner_network
             .graph
             .nodes()
             .filter(|&n| n != node_id && !ancestors.contains(&n))
             .collect::<Vec<_>>();
+        targets.sort();
 
-        log::info!("Running dijkstra on {node_name} ({}/{}) over {} edges", idx, dag.0.inner_network.graph.node_count(), &cache.candidate.graph.edge_count());
         // and calculate paths!
-        calculate_paths(
-            &mut paths_parents,
-            &cache.candidate.graph,
-            node_id,
-            &targets,
-            &targets,
-        )?;
+        let relaxations = if negative_weights {
+            log::info!("Running Bellman-Ford on {node_name} ({}/{}) over {} edges", idx, dag.0.inner_network.graph.node_count(), &candidate.graph.edge_count());
+            bellman_ford_paths(&mut paths_parents, &candidate.graph, node_id, &targets).map_err(
+                |NegativeCycleError(node)| GrowError::NegativeCycle(dag.0.display_name(node)),
+            )?
+        } else {
+            log::info!("Running dijkstra on {node_name} ({}/{}) over {} edges", idx, dag.0.inner_network.graph.node_count(), &candidate.graph.edge_count());
+            match cache.options.scaled_precision {
+                None => calculate_paths_in(
+                    &mut cache.scratch,
+                    &mut paths_parents,
+                    &candidate.graph,
+                    node_id,
+                    &targets,
+                    &targets,
+                )?,
+                Some(precision) => calculate_paths_scaled_in(
+                    &mut cache.scaled_scratch,
+                    &mut paths_parents,
+                    &candidate.graph,
+                    node_id,
+                    &targets,
+                    &targets,
+                    precision,
+                )?,
+            }
+        };
+        if let Some(diagnostics) = diagnostics.as_deref_mut() {
+            diagnostics.relaxations += relaxations;
+        }
 
+        if let Some((ends, _)) = &focus {
+            targets.retain(|target| ends.contains(target));
+        }
         all_targets.insert(node_id, targets);
     }
 
@@ -116,37 +866,27 @@ pub fn produce_dag<C: Cost>(
         .flat_map(|(source, targets)| {
             let mut paths = Vec::with_capacity(targets.len());
             for target in targets {
-                let mut path = vec![];
-                let mut current_loop_parent = Some(target);
-                while let Some(current_parent) = current_loop_parent {
-                    path.push(current_parent);
-                    current_loop_parent = paths_parents
-                        .get(&(source, current_parent))
-                        .and_then(|val| val.1)
-                }
+                let path = reconstruct_path(&paths_parents, source, target);
                 if path.len() < 2 {
                     continue;
                 }
-                path.reverse();
+                if let Some((_, unreached)) = &focus {
+                    if target == Either::Right(SuperNode::Target) && !unreached.contains(&path[path.len() - 2]) {
+                        continue;
+                    }
+                }
                 paths.push(path);
             }
 
             paths
         })
         .collect::<Vec<_>>();
+    // Every edge of a path is in the candidate graph, which was stripped of the DAG's edges.
+    debug_assert!(paths.iter().all(|path| adds_new_edge(dag, path)));
+    cache.paths = paths_parents;
+    cache.ancestors = ancestor_cache;
 
-    // Calculate the best possible path given the cost function.
-    let best_path = paths.into_iter().min_by(|x, y| {
-        cost.relative_cost_of(interactome, dag, x)
-            .total_cmp(&cost.relative_cost_of(interactome, dag, y))
-    });
-
-    Ok(best_path.map(|best_path| {
-        (
-            cost.relative_cost_of(interactome, dag, &best_path),
-            best_path,
-        )
-    }))
+    Ok(paths)
 }
 
 pub fn grow<C: Cost>(
@@ -154,26 +894,1062 @@ pub fn grow<C: Cost>(
     dag: &mut PartialDag<()>,
     cache: &mut GrowthCache,
     cost: &mut C,
-) -> Result<Option<(f64, Vec<Either<usize, SuperNode>>)>, NetworkIndexError> {
+) -> Result<Option<ScoredPath>, GrowError> {
     // grab the best path
     let next_best_path = produce_dag(interactome, dag, cache, cost)?;
 
     // and add it to the DAG.
-    if let Some((weight, next_best_path)) = next_best_path {
-        log::info!("Writing a path of length {}", next_best_path.len());
+    Ok(next_best_path.and_then(|path| add_path(interactome, dag, cache, path)))
+}
+
+/// Like `grow`, but also reports how much work was done to find the path, as in `produce_dag_with_diagnostics`.
+pub fn grow_with_diagnostics<C: Cost>(
+    interactome: &Interactome<Weight>,
+    dag: &mut PartialDag<()>,
+    cache: &mut GrowthCache,
+    cost: &mut C,
+) -> Result<(Option<ScoredPath>, GrowDiagnostics), GrowError> {
+    let (path, diagnostics) = produce_dag_with_diagnostics(interactome, dag, cache, cost)?;
 
-        for i in 0..next_best_path.len() - 1 {
-            let source_node = next_best_path[i];
-            let target_node = next_best_path[i + 1];
+    Ok((path.and_then(|path| add_path(interactome, dag, cache, path)), diagnostics))
+}
+
+/// A path grown by `grow_explained`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GrowResult {
+    /// The relative cost of the path, as in `ScoredPath`.
+    pub weight: f64,
+    pub path: Vec<Either<usize, SuperNode>>,
+    /// Each edge the path added to the DAG, by name, with its contribution to `Self::weight`
+    /// (see `Cost::edge_contributions`), where super-nodes are named as in `Interactome::display_name`.
+    /// This is empty for costs which don't decompose per-edge.
+    pub breakdown: Vec<(String, String, f64)>,
+    /// How much work was done to find the path, as in `produce_dag_with_diagnostics`.
+    pub diagnostics: GrowDiagnostics,
+}
+
+/// Like `grow`, but also breaks the grown path's cost down per added edge.
+pub fn grow_explained<C: Cost>(
+    interactome: &Interactome<Weight>,
+    dag: &mut PartialDag<()>,
+    cache: &mut GrowthCache,
+    cost: &mut C,
+) -> Result<Option<GrowResult>, GrowError> {
+    grow_once(interactome, dag, cache, cost, &rank_paths, true)
+}
+
+/// Scores and sorts candidate paths: either `rank_paths`, or `rank_paths_parallel` on some thread pool.
+type RankPaths<'a, C> = dyn Fn(
+        &Interactome<Weight>,
+        &PartialDag<()>,
+        &GrowthOptions,
+        &mut C,
+        Vec<Vec<Either<usize, SuperNode>>>,
+        Option<&mut GrowDiagnostics>,
+    ) -> Vec<ScoredPath>
+    + 'a;
+
+/// Grows a path as in `grow_explained`, ranking candidates with `rank`.
+/// This leaves `GrowResult::breakdown` empty and `GrowDiagnostics::improvement` unset unless `explain` is set.
+fn grow_once<C: Cost>(
+    interactome: &Interactome<Weight>,
+    dag: &mut PartialDag<()>,
+    cache: &mut GrowthCache,
+    cost: &mut C,
+    rank: &RankPaths<C>,
+    explain: bool,
+) -> Result<Option<GrowResult>, GrowError> {
+    let mut diagnostics = GrowDiagnostics::default();
+    let best_path = {
+        enter_span!(INFO, "produce_dag", dag_nodes = dag.0.inner_network.node_count());
+        let paths = candidate_paths(interactome, dag, cache, Some(&mut diagnostics))?;
+        let main = cache.main_interactome(interactome);
+        let candidates = rank(&main, dag, &cache.options, cost, paths, Some(&mut diagnostics));
+        pick_best(dag, cost, candidates)
+    };
+    let Some((weight, path)) = best_path else {
+        return Ok(None);
+    };
 
-            dag.0
-                .inner_network
-                .graph
-                .add_edge(source_node, target_node, ());
+    let breakdown = if explain {
+        let main = cache.main_interactome(interactome);
+        let name = |node| main.display_name(node);
+        diagnostics.improvement = Some(route_improvement(&main, dag, &path));
+
+        cost.edge_contributions(&main, dag, &path)
+            .into_iter()
+            .map(|(a, b, contribution)| (name(a), name(b), contribution))
+            .collect()
+    } else {
+        vec![]
+    };
+
+    Ok(add_path(interactome, dag, cache, (weight, path))
+        .map(|(weight, path)| GrowResult { weight, path, breakdown, diagnostics }))
+}
+
+/// Like `grow`, but scores the candidate paths in parallel on the current rayon thread pool.
+/// This picks the same path as `grow`, and mainly pays off for expensive costs such as `PathCost`.
+pub fn grow_parallel<C: Cost + Clone + Send + Sync>(
+    interactome: &Interactome<Weight>,
+    dag: &mut PartialDag<()>,
+    cache: &mut GrowthCache,
+    cost: &mut C,
+) -> Result<Option<ScoredPath>, GrowError> {
+    Ok(grow_parallel_with_diagnostics(interactome, dag, cache, cost)?.0)
+}
+
+/// Like `grow_parallel`, but also reports how much work was done to find the path, as in
+/// `produce_dag_with_diagnostics`. Paths enumerated by every thread's clone of `cost` are counted.
+pub fn grow_parallel_with_diagnostics<C: Cost + Clone + Send + Sync>(
+    interactome: &Interactome<Weight>,
+    dag: &mut PartialDag<()>,
+    cache: &mut GrowthCache,
+    cost: &mut C,
+) -> Result<(Option<ScoredPath>, GrowDiagnostics), GrowError> {
+    let mut diagnostics = GrowDiagnostics::default();
+    let best_path = {
+        enter_span!(INFO, "produce_dag", dag_nodes = dag.0.inner_network.node_count());
+        let paths = candidate_paths(interactome, dag, cache, Some(&mut diagnostics))?;
+        let main = cache.main_interactome(interactome);
+        let candidates = rank_paths_parallel(&main, dag, &cache.options, cost, paths, Some(&mut diagnostics));
+        let best_path = pick_best(dag, cost, candidates);
+        diagnostics.improvement = best_path.as_ref().map(|(_, path)| route_improvement(&main, dag, path));
+        best_path
+    };
+
+    Ok((best_path.and_then(|path| add_path(interactome, dag, cache, path)), diagnostics))
+}
+
+/// Writes a path produced by `produce_dag` into `dag`, returning it back.
+/// Paths with fewer than two nodes have no edges to add, and are dropped.
+///
+/// The path is written all at once or not at all: its edges and names are staged and checked first,
+/// so that a path which would break the DAG (see `closes_cycle`) leaves it untouched.
+fn add_path(
+    interactome: &Interactome<Weight>,
+    dag: &mut PartialDag<()>,
+    cache: &mut GrowthCache,
+    (weight, path): ScoredPath,
+) -> Option<ScoredPath> {
+    if path.len() < 2 {
+        log::warn!("Refusing to write a path of length {}, which has no edges.", path.len());
+        return None;
+    }
+
+    let interactome = cache.main_interactome(interactome);
+
+    // Newly-added nodes need to be resolvable by name from the DAG as well.
+    let names = path
+        .iter()
+        .filter_map(|node| node.left())
+        .filter(|node| !dag.0.inner_network.id_map.contains_right(node))
+        .filter_map(|node| Some((interactome.inner_network.id_from_idx(node)?.clone(), node)))
+        .collect::<Vec<_>>();
+    let edges = path
+        .windows(2)
+        .filter(|edge| !dag.0.inner_network.graph.contains_edge(edge[0], edge[1]))
+        .map(|edge| (edge[0], edge[1]))
+        .collect::<Vec<_>>();
+
+    if closes_cycle(dag, &path) {
+        log::warn!("Refusing to write a path of length {}, which would make the DAG cyclic.", path.len());
+        return None;
+    }
+
+    let interactions = edges.iter().filter(|(a, b)| a.is_left() && b.is_left()).count();
+    cache.max_edges_reached = cache.options.max_edges.is_some_and(|max| dag.interaction_count() + interactions > max);
+    if cache.max_edges_reached {
+        log::info!("Refusing to write a path adding {interactions} edges, which would exceed the maximum edge count.");
+        return None;
+    }
+
+    log::info!("Writing a path of length {}", path.len());
+    // Cached ancestors are only kept up to date if they were for this DAG to begin with.
+    let mut ancestors = cache.ancestors.take().filter(|ancestors| ancestors.is_current(dag));
+    for (name, node) in names {
+        dag.0.inner_network.id_map.insert(name, node);
+    }
+    for (a, b) in edges {
+        dag.0.inner_network.graph.add_edge(a, b, ());
+        if let Some(ancestors) = &mut ancestors {
+            ancestors.add_edge(a, b);
+        }
+    }
+    cache.ancestors =
+        ancestors.map(|ancestors| AncestorCache { fingerprint: AncestorCache::fingerprint(dag), ..ancestors });
+
+    if let Some(candidate) = cache.candidate.as_mut().filter(|_| cache.options.edge_disjoint) {
+        for edge in path.windows(2) {
+            candidate.graph.remove_edge(edge[0], edge[1]);
+        }
+    }
+
+    Some((weight, path))
+}
+
+/// Whether adding `path` to `dag` would make it cyclic: that is, whether some node along the path
+/// already reaches an earlier node of the path in `dag`. Since the path's own edges only lead forward,
+/// any cycle through them has to come back this way.
+fn closes_cycle(dag: &PartialDag<()>, path: &[Either<usize, SuperNode>]) -> bool {
+    let graph = &dag.0.inner_network.graph;
+    (1..path.len()).any(|later| {
+        if !graph.contains_node(path[later]) {
+            return false;
+        }
+
+        let earlier = &path[..later];
+        let mut dfs = Dfs::new(graph, path[later]);
+        iter::from_fn(|| dfs.next(graph)).any(|node| earlier.contains(&node))
+    })
+}
+
+/// Grows `dag` up to `k` times, returning each grown path in order.
+/// This stops early once no more paths can be constructed, once `GrowthOptions::until` is met,
+/// or once the next path would exceed `GrowthOptions::max_edges`.
+///
+/// With `GrowthOptions::rotate_targets`, an iteration whose target has no path left toward it moves on
+/// to the following targets instead, so one exhausted target doesn't halt growth toward the others.
+/// Growth then only stops once a full cycle through every target yields no path. Exhausted targets are
+/// retried on later turns, as growing toward another target can give them new DAG nodes to grow from.
+///
+/// If given, `on_grow` is called every time a path is added to the DAG (see `GrowHook`),
+/// which lets grown paths be streamed out as they come.
+pub fn grow_k<C: Cost>(
+    interactome: &Interactome<Weight>,
+    dag: &mut PartialDag<()>,
+    cache: &mut GrowthCache,
+    cost: &mut C,
+    k: usize,
+    on_grow: Option<&mut GrowHook>,
+) -> Result<Vec<ScoredPath>, GrowError> {
+    grow_k_ranked(interactome, dag, cache, cost, &rank_paths, k, on_grow)
+}
+
+/// Like `grow_k`, but scores the candidate paths in parallel on `pool`, as in `grow_parallel`.
+/// Only the scoring runs on `pool`, so `on_grow` is still called on this thread.
+pub fn grow_k_parallel<C: Cost + Clone + Send + Sync>(
+    interactome: &Interactome<Weight>,
+    dag: &mut PartialDag<()>,
+    cache: &mut GrowthCache,
+    cost: &mut C,
+    pool: &ThreadPool,
+    k: usize,
+    on_grow: Option<&mut GrowHook>,
+) -> Result<Vec<ScoredPath>, GrowError> {
+    let rank = |interactome: &_, dag: &_, options: &_, cost: &mut _, paths, diagnostics: Option<&mut _>| {
+        pool.install(|| rank_paths_parallel(interactome, dag, options, cost, paths, diagnostics))
+    };
+    grow_k_ranked(interactome, dag, cache, cost, &rank, k, on_grow)
+}
+
+/// Called by `grow_k` with the iteration (counting from 1), the path as in `grow_explained`,
+/// and the DAG just after the path was added to it.
+pub type GrowHook<'a> = dyn FnMut(usize, &GrowResult, &PartialDag<()>) + 'a;
+
+fn grow_k_ranked<C: Cost>(
+    interactome: &Interactome<Weight>,
+    dag: &mut PartialDag<()>,
+    cache: &mut GrowthCache,
+    cost: &mut C,
+    rank: &RankPaths<C>,
+    k: usize,
+    mut on_grow: Option<&mut GrowHook>,
+) -> Result<Vec<ScoredPath>, GrowError> {
+    // Only paths passed to `on_grow` need their cost broken down.
+    let explain = on_grow.is_some();
+    let mut paths = Vec::with_capacity(k);
+
+    for i in 1..=k {
+        if cache.options.until.is_met(interactome, dag) {
+            log::info!("Every target is connected. Stopping at iteration {i}.");
+            break;
+        }
+
+        log::info!("Growing DAGs: iteration {i}.");
+        enter_span!(INFO, "grow", iteration = i);
+        let grown = if cache.options.rotate_targets {
+            grow_rotating(interactome, dag, cache, cost, rank, i - 1, explain)?
+        } else {
+            grow_once(interactome, dag, cache, cost, rank, explain)?
+        };
+
+        match grown {
+            Some(grown) => {
+                if let Some(on_grow) = &mut on_grow {
+                    on_grow(i, &grown, dag);
+                }
+                paths.push((grown.weight, grown.path));
+            }
+            None if cache.max_edges_reached() => {
+                log::info!("The DAG has reached its maximum edge count. Stopping at iteration {i}.");
+                break;
+            }
+            None => {
+                log::warn!("No more paths could be constructed. Stopping at iteration {i}.");
+                break;
+            }
+        }
+    }
+
+    Ok(paths)
+}
+
+/// Grows `dag` toward the `turn`th target (modulo the number of targets). If no path grows toward it,
+/// the following targets are tried in turn.
+fn grow_rotating<C: Cost>(
+    interactome: &Interactome<Weight>,
+    dag: &mut PartialDag<()>,
+    cache: &mut GrowthCache,
+    cost: &mut C,
+    rank: &RankPaths<C>,
+    turn: usize,
+    explain: bool,
+) -> Result<Option<GrowResult>, GrowError> {
+    let focus = cache.options.target_focus.take();
+    let targets = &interactome.targets;
+
+    let mut grown = Ok(None);
+    for offset in 0..targets.len() {
+        let target = targets[(turn + offset) % targets.len()];
+        log::debug!("Focusing on the target {target}.");
+
+        cache.options.target_focus = Some(vec![target]);
+        grown = grow_once(interactome, dag, cache, cost, rank, explain);
+        if !matches!(grown, Ok(None)) || cache.max_edges_reached() {
+            break;
+        }
+
+        log::info!(
+            "No path grows toward the target {}, moving on to the next one.",
+            interactome.display_name(Either::Left(target))
+        );
+    }
+
+    cache.options.target_focus = focus;
+    grown
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::{
+        alg::cost::{EdgeCost, PathCost},
+        parsing::{
+            data::EmptyTupleDataFactory,
+            weight::{TypedWeightDataFactory, WeightDataFactory},
+        },
+    };
+
+    use super::*;
+
+    /// The interactome over `lines` between `sources` and `targets`, along with the DAG of `dag_edges` over it.
+    fn fixture(
+        lines: &[&str],
+        sources: &[&str],
+        targets: &[&str],
+        dag_edges: &[(&str, &str)],
+    ) -> (Interactome<Weight>, PartialDag<()>) {
+        let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        let (sources, targets) = (names(sources), names(targets));
+
+        let network =
+            Network::from_lines::<WeightDataFactory, _>(lines.iter().map(|line| Ok(line.to_string()))).unwrap();
+        let interactome = Interactome::attach_sources_and_targets(network, &sources, &targets, true).unwrap();
+        let dag_edges = dag_edges.iter().map(|&(a, b)| (a.to_string(), b.to_string())).collect::<Vec<_>>();
+        let dag = PartialDag::from_edges(&dag_edges, &interactome.inner_network.id_map, &sources, &targets).unwrap();
+
+        (interactome, dag)
+    }
+
+    /// The interactome and DAG of the fixture folder `name`, between the single `source` and `target`.
+    fn folder_fixture(name: &str, source: &str, target: &str) -> (Interactome<Weight>, PartialDag<()>) {
+        let folder = Path::new("./tests/fixtures").join(name);
+        let (sources, targets) = (vec![source.to_string()], vec![target.to_string()]);
+
+        let network = Network::from_file::<WeightDataFactory>(&folder.join("interactome.txt")).unwrap();
+        let interactome = Interactome::attach_sources_and_targets(network, &sources, &targets, true).unwrap();
+        let dag = PartialDag::new(
+            Network::from_file_using_id_map::<EmptyTupleDataFactory>(
+                &folder.join("dag.txt"),
+                &interactome.inner_network.id_map,
+                false,
+            )
+            .unwrap(),
+            &sources,
+            &targets,
+        )
+        .unwrap();
+
+        (interactome, dag)
+    }
+
+    /// The `fan` fixture: A fans out to X and Y, which both reach T, directly or through M and N.
+    fn fan_fixture() -> (Interactome<Weight>, PartialDag<()>) {
+        folder_fixture("fan", "A", "T")
+    }
+
+    #[test]
+    fn add_path_skips_short_paths() {
+        let (interactome, mut dag) = folder_fixture("triangle", "A", "C");
+        let mut cache = GrowthCache::new(interactome.clone());
+        let edge_count = dag.0.inner_network.edge_count();
+
+        // Stand-ins for a `produce_dag` which returned degenerate paths.
+        let b = interactome.inner_network.as_nodes(&["B"]).unwrap();
+        assert!(add_path(&interactome, &mut dag, &mut cache, (0.0, b)).is_none());
+        assert!(add_path(&interactome, &mut dag, &mut cache, (0.0, vec![])).is_none());
+        assert_eq!(dag.0.inner_network.edge_count(), edge_count);
+    }
+
+    #[test]
+    fn add_path_rejects_cycles_atomically() {
+        let (interactome, mut dag) = fixture(
+            &["A\tB\t1", "B\tC\t1", "C\tX\t1", "X\tA\t1"],
+            &["A"],
+            &["C"],
+            &[("A", "B"), ("B", "C")],
+        );
+        let mut cache = GrowthCache::new(interactome.clone());
+        let before = dag.clone();
+
+        // C -> X is fine on its own, but X -> A closes the loop back through A -> B -> C.
+        let path = interactome.inner_network.as_nodes(&["C", "X", "A"]).unwrap();
+        assert!(add_path(&interactome, &mut dag, &mut cache, (1.0, path)).is_none());
+        assert_eq!(dag.0.inner_network.to_edge_list(), before.0.inner_network.to_edge_list());
+        assert_eq!(dag.0.inner_network.node_count(), before.0.inner_network.node_count());
+        assert_eq!(dag.0.inner_network.id_map, before.0.inner_network.id_map);
+
+        let path = interactome.inner_network.as_nodes(&["C", "X"]).unwrap();
+        assert!(add_path(&interactome, &mut dag, &mut cache, (1.0, path)).is_some());
+        assert!(dag.contains_edge_by_name("C", "X").unwrap());
+    }
+
+    #[test]
+    fn searched_candidate_excludes_ancestors() {
+        // The triangle, along with A -> X, so that A stays in the candidate graph.
+        let (interactome, dag) = fixture(
+            &["A\tB\t1", "A\tC\t1", "B\tC\t1", "A\tX\t1"],
+            &["A"],
+            &["C"],
+            &[("A", "C"), ("A", "B")],
+        );
+        let cache = GrowthCache::new(interactome.clone());
+
+        let nodes = interactome.inner_network.as_nodes(&["A", "B", "C"]).unwrap();
+        assert!(searched_candidate(&dag, &cache, nodes[0]).unwrap().graph.contains_node(nodes[0]));
+
+        // ...but A is an ancestor of B.
+        let candidate = searched_candidate(&dag, &cache, nodes[1]).unwrap();
+        assert!(!candidate.graph.contains_node(nodes[0]));
+        assert!(candidate.graph.contains_edge(nodes[1], nodes[2]));
+
+        assert!(searched_candidate(&dag, &cache, Either::Left(usize::MAX)).is_none());
+    }
+
+    #[test]
+    fn candidates_skip_existing_routes() {
+        let (interactome, dag) =
+            fixture(&["A\tB\t1", "A\tC\t1", "B\tC\t1"], &["A"], &["C"], &[("A", "B"), ("A", "C")]);
+
+        // A -> C is already in the DAG, so it would cost nothing under `EdgeCost`, but it is never a candidate.
+        let new = interactome.inner_network.as_nodes(&["B", "C"]).unwrap();
+        let mut cache = GrowthCache::new(interactome.clone());
+        let candidates = produce_dag_candidates(&interactome, &dag, &mut cache, &mut EdgeCost).unwrap();
+
+        assert_eq!(candidates, vec![(1.0, new)]);
+    }
+
+    #[test]
+    fn cost_epsilon_ties_noisy_costs() {
+        /// Makes paths through X cheaper than any other, but only by a single rounding error.
+        struct NoisyCost(Either<usize, SuperNode>);
+        impl Cost for NoisyCost {
+            fn relative_cost_of(
+                &mut self,
+                _: &Interactome<Weight>,
+                _: &PartialDag<()>,
+                nodes: &[Either<usize, SuperNode>],
+            ) -> f64 {
+                if nodes.contains(&self.0) { 1.0 } else { 1.0 + 1e-15 }
+            }
         }
 
-        return Ok(Some((weight, next_best_path)));
+        let (interactome, dag) = fixture(&["A\tX\t1", "X\tC\t1", "A\tC\t1"], &["A"], &["C"], &[]);
+
+        let detour = interactome.inner_network.as_nodes(&["A", "X", "C"]).unwrap();
+        let direct = interactome.inner_network.as_nodes(&["A", "C"]).unwrap();
+        let mut cost = NoisyCost(detour[1]);
+        let mut rank = |cost_epsilon| {
+            let options = GrowthOptions { cost_epsilon, ..Default::default() };
+            rank_paths(&interactome, &dag, &options, &mut cost, vec![direct.clone(), detour.clone()], None)
+                .into_iter()
+                .map(|(_, path)| path)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(rank(0.0), vec![detour.clone(), direct.clone()]);
+        // Within epsilon, the shorter path wins the tie.
+        assert_eq!(rank(1e-12), vec![direct.clone(), detour.clone()]);
     }
 
-    Ok(None)
+    #[test]
+    fn edge_cost_breakdown_sums_to_weight() {
+        let (interactome, initial_dag) = folder_fixture("triangle", "A", "C");
+        let mut dag = initial_dag.clone();
+        let mut cache = GrowthCache::new(interactome.clone());
+
+        let grown = grow_explained(&interactome, &mut dag, &mut cache, &mut EdgeCost).unwrap().unwrap();
+        assert!(!grown.breakdown.is_empty());
+        assert_eq!(grown.breakdown.iter().map(|(_, _, contribution)| contribution).sum::<f64>(), grown.weight);
+        for (a, b, _) in &grown.breakdown {
+            assert!(dag.contains_edge_by_name(a, b).unwrap());
+        }
+
+        // `PathCost` doesn't decompose per-edge.
+        let mut dag = initial_dag;
+        let grown = grow_explained(&interactome, &mut dag, &mut cache, &mut PathCost).unwrap().unwrap();
+        assert!(grown.breakdown.is_empty());
+    }
+
+    #[test]
+    fn parallel_ranking_matches_serial() {
+        let (interactome, dag) = fan_fixture();
+
+        let mut cache = GrowthCache::new(interactome.clone());
+        let paths = candidate_paths(&interactome, &dag, &mut cache, None).unwrap();
+        assert!(paths.len() > 1);
+        let mut serial_diagnostics = GrowDiagnostics::default();
+        let mut parallel_diagnostics = GrowDiagnostics::default();
+        let serial =
+            rank_paths(&interactome, &dag, &cache.options, &mut PathCost, paths.clone(), Some(&mut serial_diagnostics));
+        let parallel = rank_paths_parallel(
+            &interactome,
+            &dag,
+            &cache.options,
+            &mut PathCost,
+            paths,
+            Some(&mut parallel_diagnostics),
+        );
+        assert_eq!(serial, parallel);
+        assert!(serial_diagnostics.paths_enumerated > 0);
+        assert_eq!(serial_diagnostics, parallel_diagnostics);
+
+        let (mut serial_dag, mut parallel_dag) = (dag.clone(), dag);
+        let mut parallel_cache = GrowthCache::new(interactome.clone());
+        for _ in 0..3 {
+            assert_eq!(
+                grow(&interactome, &mut serial_dag, &mut cache, &mut EdgeCost).unwrap(),
+                grow_parallel(&interactome, &mut parallel_dag, &mut parallel_cache, &mut EdgeCost).unwrap(),
+            );
+        }
+    }
+
+    #[test]
+    fn rotate_targets_alternates() {
+        // Both routes to T1 are cheaper than the one to T2.
+        let (interactome, dag) = fixture(
+            &[
+                "S\tT1\t1", "S\tT2\t1", "S\ta\t0.1", "a\tT1\t0.1", "S\tc\t0.15", "c\tT1\t0.15", "S\tb\t0.5",
+                "b\tT2\t0.5",
+            ],
+            &["S"],
+            &["T1", "T2"],
+            &[("S", "T1"), ("S", "T2")],
+        );
+
+        let grown_through = |rotate_targets: bool| {
+            let mut dag = dag.clone();
+            let mut cache = GrowthCache::new(interactome.clone());
+            cache.options.rotate_targets = rotate_targets;
+
+            let grown = grow_k(&interactome, &mut dag, &mut cache, &mut EdgeCost, 3, None).unwrap();
+            assert!(cache.options.target_focus.is_none());
+
+            grown
+                .into_iter()
+                .map(|(_, path)| match path[1] {
+                    Either::Left(id) => interactome.inner_network.id_map.get_by_right(&id).unwrap().clone(),
+                    Either::Right(_) => panic!("grew a path straight out of a super node"),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(grown_through(false), vec!["a", "c", "b"]);
+        assert_eq!(grown_through(true), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn focus_on_targets_outside_the_dag() {
+        // The DAG hasn't reached T2 yet, and every route to T1 is cheaper than the one to T2.
+        let (interactome, dag) = fixture(
+            &["S\tT1\t1", "S\ta\t0.1", "a\tT1\t0.1", "S\tc\t0.2", "c\tT1\t0.2", "S\tb\t0.5", "b\tT2\t0.5"],
+            &["S"],
+            &["T1", "T2"],
+            &[("S", "T1")],
+        );
+        let t2 = interactome.inner_network.get_node("T2").unwrap();
+
+        let mut cache = GrowthCache::new(interactome.clone());
+        cache.options.target_focus = Some(vec![t2]);
+        let (_, path) = produce_dag(&interactome, &dag, &mut cache, &mut EdgeCost).unwrap().unwrap();
+        assert_eq!(interactome.strip_super_and_name(&path).unwrap(), vec!["S", "b", "T2"]);
+
+        // Rotation reaches T2 on its first turn, rather than skipping it for T1.
+        let mut cache = GrowthCache::new(interactome.clone());
+        cache.options.rotate_targets = true;
+        let grown = grow_k(&interactome, &mut dag.clone(), &mut cache, &mut EdgeCost, 3, None).unwrap();
+        let through = grown.iter().map(|(_, path)| interactome.name_from_idx(path[1]).unwrap()).collect::<Vec<_>>();
+        assert_eq!(through, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn rotation_continues_past_exhausted_targets() {
+        // The only route to T1 is already in the DAG.
+        let (interactome, mut dag) = fixture(
+            &["S\tT1\t1", "S\tT2\t1", "S\tb\t0.5", "b\tT2\t0.5", "S\td\t0.75", "d\tT2\t0.75"],
+            &["S"],
+            &["T1", "T2"],
+            &[("S", "T1"), ("S", "T2")],
+        );
+        let mut cache = GrowthCache::new(interactome.clone());
+        cache.options.rotate_targets = true;
+
+        let grown = grow_k(&interactome, &mut dag, &mut cache, &mut EdgeCost, 4, None).unwrap();
+        let through = grown.iter().map(|(_, path)| interactome.name_from_idx(path[1]).unwrap()).collect::<Vec<_>>();
+        // T1 is exhausted on every turn, while T2 still grows, until both are exhausted.
+        assert_eq!(through, vec!["b", "d"]);
+    }
+
+    #[test]
+    fn diagnostics_report_route_improvement() {
+        // The DAG already routes A -> M -> D at a weight of 2, while A -> X -> D only weighs 0.5.
+        let (interactome, dag) = fixture(
+            &["A\tM\t1", "M\tD\t1", "A\tX\t0.25", "X\tD\t0.25"],
+            &["A"],
+            &["D"],
+            &[("A", "M"), ("M", "D")],
+        );
+
+        let mut cache = GrowthCache::new(interactome.clone());
+        let (path, diagnostics) =
+            produce_dag_with_diagnostics(&interactome, &dag, &mut cache, &mut EdgeCost).unwrap();
+        let (_, path) = path.unwrap();
+        assert_eq!(interactome.strip_super_and_name(&path).unwrap(), vec!["A", "X", "D"]);
+        assert_eq!(diagnostics.improvement, Some(1.5));
+
+        // Without any route between A and D, any path is an infinite improvement.
+        let x = interactome.inner_network.as_nodes(&["A", "X"]).unwrap();
+        assert_eq!(route_improvement(&interactome, &dag, &x), f64::INFINITY);
+    }
+
+    #[test]
+    fn cached_ancestors_match_fresh_ones() {
+        let (interactome, dag) = fixture(
+            &[
+                "A\tM\t1", "M\tD\t1", "A\tX\t0.2", "X\tM\t0.3", "X\tY\t0.4", "Y\tD\t0.1", "M\tE\t0.5",
+                "Y\tE\t0.6", "A\tZ\t0.7", "Z\tY\t0.1", "Z\tE\t0.9",
+            ],
+            &["A"],
+            &["D", "E"],
+            &[("A", "M"), ("M", "D")],
+        );
+
+        let grow_with = |cache_ancestors: bool| {
+            let (mut dag, mut cache) = (dag.clone(), GrowthCache::new(interactome.clone()));
+            cache.options.cache_ancestors = cache_ancestors;
+            let grown = grow_k(&interactome, &mut dag, &mut cache, &mut EdgeCost, 6, None).unwrap();
+            (grown, dag, cache)
+        };
+
+        let (fresh, _, _) = grow_with(false);
+        let (cached, grown_dag, cache) = grow_with(true);
+        assert!(fresh.len() > 3);
+        assert_eq!(cached, fresh);
+
+        // The incrementally updated ancestors are those of the grown DAG.
+        let ancestors = cache.ancestors.unwrap();
+        assert!(ancestors.is_current(&grown_dag));
+        assert_eq!(ancestors.ancestors, AncestorCache::new(&grown_dag).ancestors);
+    }
+
+    #[test]
+    fn cached_ancestors_follow_the_dag() {
+        let (interactome, dag) = fixture(
+            &["A\tB\t1", "B\tT\t1", "A\tC\t0.5", "C\tT\t0.5", "A\tE\t1", "E\tT\t1", "A\tX\t2", "X\tT\t2"],
+            &["A"],
+            &["T"],
+            &[("A", "B"), ("B", "T")],
+        );
+        let mut cache = GrowthCache::new(interactome.clone());
+        cache.options.cache_ancestors = true;
+        let (_, path) = grow(&interactome, &mut dag.clone(), &mut cache, &mut EdgeCost).unwrap().unwrap();
+        assert_eq!(interactome.strip_super_and_name(&path).unwrap(), vec!["A", "C", "T"]);
+
+        // Another DAG of the same size as the grown one, with a node the cached ancestors have never seen.
+        let edge = |a: &str, b: &str| (a.to_string(), b.to_string());
+        let other = PartialDag::from_edges(
+            &[edge("A", "C"), edge("C", "T"), edge("A", "E"), edge("E", "T")],
+            &interactome.inner_network.id_map,
+            &["A".to_string()],
+            &["T".to_string()],
+        )
+        .unwrap();
+
+        let fresh = grow(&interactome, &mut other.clone(), &mut GrowthCache::new(interactome.clone()), &mut EdgeCost);
+        let mut grown = other.clone();
+        let cached = grow(&interactome, &mut grown, &mut cache, &mut EdgeCost);
+        assert_eq!(cached.unwrap(), fresh.unwrap());
+        assert_eq!(cache.ancestors.unwrap().ancestors, AncestorCache::new(&grown).ancestors);
+    }
+
+    #[test]
+    fn bottleneck_tie_break_prefers_balanced_paths() {
+        // Against the DAG A -> M -> D, A -> B -> M and M -> C -> D both cost 1 over the same number of edges,
+        // but A -> B -> M hinges on a single weak edge.
+        let (interactome, dag) = fixture(
+            &["A\tM\t1", "M\tD\t1", "A\tB\t0.1", "B\tM\t0.9", "M\tC\t0.5", "C\tD\t0.5"],
+            &["A"],
+            &["D"],
+            &[("A", "M"), ("M", "D")],
+        );
+
+        let first_hop = |tie_break: TieBreak| {
+            let mut cache = GrowthCache::new(interactome.clone());
+            cache.options.tie_break = tie_break;
+            let (cost, path) = produce_dag(&interactome, &dag, &mut cache, &mut EdgeCost).unwrap().unwrap();
+            assert_eq!(cost, 1.0);
+            interactome.name_from_idx(path[1]).unwrap()
+        };
+
+        assert_eq!(first_hop(TieBreak::Length), "B");
+        assert_eq!(first_hop(TieBreak::Bottleneck), "C");
+    }
+
+    #[test]
+    fn grow_until_all_targets_connected() {
+        // Against the DAG A -> M, M -> T1 and then M -> T2 are the cheapest paths, while A -> X -> M comes after.
+        let (interactome, dag) = fixture(
+            &["A\tM\t1", "M\tT1\t0.5", "M\tT2\t0.6", "A\tX\t0.35", "X\tM\t0.35"],
+            &["A"],
+            &["T1", "T2"],
+            &[("A", "M")],
+        );
+
+        let grow_until = |until: StopCondition| {
+            let (mut dag, mut cache) = (dag.clone(), GrowthCache::new(interactome.clone()));
+            cache.options.until = until;
+            let grown = grow_k(&interactome, &mut dag, &mut cache, &mut EdgeCost, 5, None).unwrap();
+            (grown.len(), dag.connects_targets(&interactome.targets))
+        };
+
+        assert!(!dag.connects_targets(&interactome.targets));
+        assert_eq!(grow_until(StopCondition::AllTargetsConnected), (2, true));
+        assert_eq!(grow_until(StopCondition::Iterations).0, 3);
+    }
+
+    #[test]
+    fn grow_from_disconnected_components() {
+        // The DAG starts as A -> B and C -> D, neither of which touch a source or target.
+        let (interactome, mut dag) = fixture(
+            &["S\tA\t1", "A\tB\t1", "B\tC\t1", "C\tD\t1", "D\tT\t1"],
+            &["S"],
+            &["T"],
+            &[("A", "B"), ("C", "D")],
+        );
+
+        let mut cache = GrowthCache::new(interactome.clone());
+        let grown = grow_k(&interactome, &mut dag, &mut cache, &mut EdgeCost, 5, None).unwrap();
+
+        // Paths grow out of and into either component, from the super-source, and into the super-target alike.
+        let grown = grown.iter().map(|(_, path)| interactome.strip_super_and_name(path).unwrap()).collect::<Vec<_>>();
+        assert_eq!(grown, vec![vec!["D", "T"], vec!["B", "C"], vec!["S", "A"]]);
+        assert!(dag.connects_targets(&interactome.targets));
+        assert_eq!(dag.0.inner_network.to_edge_list(), "A\tB\nB\tC\nC\tD\nD\tT\nS\tA\n");
+    }
+
+    #[test]
+    fn edge_disjoint_paths() {
+        let (interactome, dag) = fan_fixture();
+        let edges = |path: &[Either<usize, SuperNode>]| {
+            path.windows(2).map(|edge| (edge[0], edge[1])).collect::<HashSet<_>>()
+        };
+
+        let mut cache = GrowthCache::new(interactome.clone());
+        cache.options.edge_disjoint = true;
+        let grown = grow_k(&interactome, &mut dag.clone(), &mut cache, &mut EdgeCost, 2, None).unwrap();
+        assert_eq!(grown.len(), 2);
+        assert!(edges(&grown[0].1).is_disjoint(&edges(&grown[1].1)));
+
+        // Starting over from the same DAG, the first two paths can't be grown again.
+        let (_, regrown) = grow(&interactome, &mut dag.clone(), &mut cache, &mut EdgeCost).unwrap().unwrap();
+        assert!(grown.iter().all(|(_, path)| edges(path).is_disjoint(&edges(&regrown))));
+
+        let mut cache = GrowthCache::new(interactome.clone());
+        grow(&interactome, &mut dag.clone(), &mut cache, &mut EdgeCost).unwrap();
+        assert_eq!(grow(&interactome, &mut dag.clone(), &mut cache, &mut EdgeCost).unwrap(), Some(grown[0].clone()));
+    }
+
+    #[test]
+    fn reset_restores_the_candidate() {
+        let (interactome, dag) = fan_fixture();
+        let available = GrowthCache::new(interactome.clone()).available_edges(&dag);
+
+        let mut cache = GrowthCache::new(interactome.clone());
+        cache.options.edge_disjoint = true;
+        cache.add_interactome_edge("A", "Z", Weight(0.25));
+        let first = grow_k(&interactome, &mut dag.clone(), &mut cache, &mut EdgeCost, 2, None).unwrap();
+        assert_ne!(cache.available_edges(&dag), available);
+
+        cache.reset(&interactome);
+        assert_eq!(cache.available_edges(&dag), available);
+        assert_eq!(grow_k(&interactome, &mut dag.clone(), &mut cache, &mut EdgeCost, 2, None).unwrap(), first);
+    }
+
+    #[test]
+    fn allowed_edge_types() {
+        let (sources, targets) = (vec!["A".to_string()], vec!["T".to_string()]);
+        let (network, types) = Network::from_lines::<TypedWeightDataFactory, _>(
+            ["A\tB\t1\tphysical", "B\tT\t1\tphysical", "A\tT\t0.5\tpredicted"]
+                .into_iter()
+                .map(|line| Ok(line.to_string())),
+        )
+        .unwrap()
+        .split_edge_types();
+        let interactome = Interactome::attach_sources_and_targets(network, &sources, &targets, true).unwrap();
+        let dag = PartialDag::from_edges(&[], &interactome.inner_network.id_map, &sources, &targets).unwrap();
+
+        let grow_over = |allowed: Option<&[&str]>| {
+            let mut cache = GrowthCache::new(interactome.clone());
+            cache.options.edge_types = allowed.map(|allowed| EdgeTypeFilter {
+                types: types.clone(),
+                allowed: allowed.iter().map(|edge_type| edge_type.to_string()).collect(),
+            });
+            let (_, path) = grow(&interactome, &mut dag.clone(), &mut cache, &mut EdgeCost).unwrap().unwrap();
+            interactome.strip_super_and_name(&path).unwrap()
+        };
+
+        assert_eq!(grow_over(None), vec!["A", "T"]);
+        assert_eq!(grow_over(Some(&["physical", "predicted"])), vec!["A", "T"]);
+        assert_eq!(grow_over(Some(&["physical"])), vec!["A", "B", "T"]);
+
+        // The filter is applied to the cache's candidate graph once, on its first growth.
+        let mut cache = GrowthCache::new(interactome.clone());
+        cache.options.edge_types = Some(EdgeTypeFilter { types, allowed: HashSet::from(["physical".to_string()]) });
+        assert_eq!(cache.available_edges(&dag).len(), 3);
+        grow(&interactome, &mut dag.clone(), &mut cache, &mut EdgeCost).unwrap();
+        assert!(cache.edge_types_applied);
+        assert_eq!(cache.available_edges(&dag).len(), 2);
+    }
+
+    #[test]
+    fn grow_hook_sees_every_path() {
+        let (interactome, dag) = fan_fixture();
+
+        let mut seen = vec![];
+        let (mut hooked_dag, mut cache) = (dag.clone(), GrowthCache::new(interactome.clone()));
+        let mut on_grow = |i, grown: &GrowResult, dag: &PartialDag<()>| {
+            assert!(!grown.breakdown.is_empty());
+            assert!(grown.path.windows(2).all(|edge| dag.0.inner_network.graph.contains_edge(edge[0], edge[1])));
+            seen.push((i, grown.weight, grown.path.clone()));
+        };
+        let hooked = grow_k(&interactome, &mut hooked_dag, &mut cache, &mut EdgeCost, 3, Some(&mut on_grow)).unwrap();
+
+        let (mut plain_dag, mut cache) = (dag.clone(), GrowthCache::new(interactome.clone()));
+        let plain = grow_k(&interactome, &mut plain_dag, &mut cache, &mut EdgeCost, 3, None).unwrap();
+
+        assert_eq!(seen.len(), 3);
+        assert_eq!(hooked, plain);
+        let expected =
+            plain.into_iter().enumerate().map(|(i, (weight, path))| (i + 1, weight, path)).collect::<Vec<_>>();
+        assert_eq!(seen, expected);
+    }
+
+    #[test]
+    fn grow_on_empty_interactome() {
+        for lines in [vec![], vec!["# only", "# comments"]] {
+            let network =
+                Network::from_lines::<WeightDataFactory, _>(lines.into_iter().map(|line| Ok(line.to_string()))).unwrap();
+            let interactome = Interactome::attach_sources_and_targets(network, &[], &[], false).unwrap();
+            let dag_network = Network::from_lines::<EmptyTupleDataFactory, _>(std::iter::empty()).unwrap();
+            let mut dag = PartialDag::new(dag_network, &[], &[]).unwrap();
+            let mut cache = GrowthCache::new(interactome.clone());
+
+            assert!(grow(&interactome, &mut dag, &mut cache, &mut EdgeCost).unwrap().is_none());
+            assert_eq!(dag.0.inner_network.edge_count(), 0);
+        }
+    }
+
+    #[test]
+    fn reused_scratch_matches_fresh() {
+        let (interactome, dag) = fan_fixture();
+
+        for scaled_precision in [None, Some(6)] {
+            let new_cache = || {
+                let mut cache = GrowthCache::new(interactome.clone());
+                cache.options.scaled_precision = scaled_precision;
+                cache
+            };
+
+            // One cache (and so one set of path-finding buffers) across the whole run...
+            let (mut reused_dag, mut cache) = (dag.clone(), new_cache());
+            let reused = grow_k(&interactome, &mut reused_dag, &mut cache, &mut EdgeCost, 3, None).unwrap();
+
+            // ...against freshly allocated buffers every iteration.
+            let mut fresh_dag = dag.clone();
+            let fresh = (0..3)
+                .map_while(|_| grow(&interactome, &mut fresh_dag, &mut new_cache(), &mut EdgeCost).unwrap())
+                .collect::<Vec<_>>();
+
+            assert_eq!(reused.len(), 3);
+            assert_eq!(reused, fresh);
+            assert_eq!(reused_dag.0.inner_network.to_edge_list(), fresh_dag.0.inner_network.to_edge_list());
+        }
+    }
+
+    #[test]
+    fn lazy_cache_builds_on_first_growth() {
+        let (interactome, dag) = fan_fixture();
+
+        let mut lazy = GrowthCache::lazy();
+        // Edges added before the first growth wait for the candidate graph.
+        lazy.add_interactome_edge("A", "Z", Weight(0.25));
+        lazy.add_interactome_edge("Z", "T", Weight(0.25));
+        assert!(lazy.candidate.is_none());
+        assert!(searched_candidate(&dag, &lazy, Either::Right(SuperNode::Source)).is_none());
+
+        let mut eager = GrowthCache::new(interactome.clone());
+        eager.add_interactome_edge("A", "Z", Weight(0.25));
+        eager.add_interactome_edge("Z", "T", Weight(0.25));
+
+        let (mut lazy_dag, mut eager_dag) = (dag.clone(), dag.clone());
+        let lazily = grow_k(&interactome, &mut lazy_dag, &mut lazy, &mut EdgeCost, 3, None).unwrap();
+        let eagerly = grow_k(&interactome, &mut eager_dag, &mut eager, &mut EdgeCost, 3, None).unwrap();
+
+        assert!(lazy.candidate.is_some());
+        assert_eq!(lazily, eagerly);
+        assert_eq!(lazily[0].0, 0.5);
+    }
+
+    #[test]
+    fn empty_nodes_are_needed_again() {
+        // B only touches the DAG edge A -> B, so it is empty once that edge is stripped.
+        let (interactome, dag) = fixture(&["A\tB\t1", "A\tC\t4"], &["A"], &["C"], &[("A", "B")]);
+        let b = interactome.inner_network.as_nodes(&["B"]).unwrap()[0];
+
+        let mut grown = vec![];
+        for keep_empty_nodes in [false, true] {
+            let mut cache = GrowthCache::new(interactome.clone());
+            cache.options.keep_empty_nodes = keep_empty_nodes;
+            let mut dag = dag.clone();
+
+            let (_, diagnostics) =
+                produce_dag_with_diagnostics(&interactome, &dag, &mut cache, &mut EdgeCost).unwrap();
+            assert_eq!(searched_candidate(&dag, &cache, b).unwrap().graph.contains_node(b), keep_empty_nodes);
+            assert_eq!(diagnostics.candidate_nodes, if keep_empty_nodes { 5 } else { 3 });
+
+            // B is needed again once it gains an edge: the candidate graph is rebuilt for every iteration,
+            // so removing it earlier doesn't hide it.
+            cache.add_interactome_edge("B", "C", Weight(1.0));
+            grown.push(grow_k(&interactome, &mut dag, &mut cache, &mut EdgeCost, 2, None).unwrap());
+        }
+
+        assert_eq!(grown[0], grown[1]);
+        let mut b_to_target = interactome.inner_network.as_nodes(&["B", "C"]).unwrap();
+        b_to_target.push(Either::Right(SuperNode::Target));
+        assert_eq!(grown[0][0].1, b_to_target);
+    }
+
+    #[test]
+    fn merge_parallel_edges_keeps_the_cheapest() {
+        let (interactome, dag) = fixture(&["S\tT\t2"], &["S"], &["T"], &[]);
+
+        let grow_with = |merge_parallel_edges: bool, weight: f64| {
+            let mut cache = GrowthCache::lazy();
+            cache.options.merge_parallel_edges = merge_parallel_edges;
+            cache.add_interactome_edge("S", "T", Weight(weight));
+            grow(&interactome, &mut dag.clone(), &mut cache, &mut EdgeCost).unwrap().unwrap().0
+        };
+
+        assert_eq!(grow_with(false, 3.0), 3.0);
+        assert_eq!(grow_with(true, 3.0), 2.0);
+        assert_eq!(grow_with(true, 1.0), 1.0);
+    }
+
+    #[test]
+    fn dead_ends_are_skipped() {
+        // Past the DAG's own edges, A only leads to Z and W, which never return to the DAG.
+        let (interactome, dag) = fixture(
+            &["S\tA\t1", "A\tT\t1", "S\tB\t1", "B\tT\t1", "A\tZ\t1", "Z\tW\t1"],
+            &["S"],
+            &["T"],
+            &[("S", "A"), ("A", "T")],
+        );
+
+        let mut cache = GrowthCache::new(interactome.clone());
+        let (path, diagnostics) = produce_dag_with_diagnostics(&interactome, &dag, &mut cache, &mut EdgeCost).unwrap();
+
+        assert_eq!(diagnostics.dead_ends, 1);
+        assert_eq!(path, Some((2.0, interactome.inner_network.as_nodes(&["S", "B", "T"]).unwrap())));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn growth_spans() {
+        use std::{collections::BTreeSet, sync::{Arc, Mutex}};
+
+        use tracing::{span, Event, Metadata, Subscriber};
+
+        /// Captures every entered span's name, alongside the name of the span it was entered within.
+        #[derive(Default)]
+        struct Capture {
+            names: Mutex<Vec<&'static str>>,
+            stack: Mutex<Vec<usize>>,
+            entered: Mutex<BTreeSet<(&'static str, Option<&'static str>)>>,
+        }
+
+        impl Subscriber for Capture {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, attrs: &span::Attributes<'_>) -> span::Id {
+                let mut names = self.names.lock().unwrap();
+                names.push(attrs.metadata().name());
+                span::Id::from_u64(names.len() as u64)
+            }
+
+            fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+            fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+            fn event(&self, _: &Event<'_>) {}
+
+            fn enter(&self, span: &span::Id) {
+                let names = self.names.lock().unwrap();
+                let mut stack = self.stack.lock().unwrap();
+                let idx = span.into_u64() as usize - 1;
+                self.entered.lock().unwrap().insert((names[idx], stack.last().map(|&parent| names[parent])));
+                stack.push(idx);
+            }
+
+            fn exit(&self, _: &span::Id) {
+                self.stack.lock().unwrap().pop();
+            }
+        }
+
+        let (interactome, mut dag) = folder_fixture("triangle", "A", "C");
+        let mut cache = GrowthCache::new(interactome.clone());
+
+        let capture = Arc::new(Capture::default());
+        tracing::subscriber::with_default(capture.clone(), || {
+            grow_k(&interactome, &mut dag, &mut cache, &mut EdgeCost, 1, None).unwrap();
+        });
+
+        assert_eq!(
+            *capture.entered.lock().unwrap(),
+            BTreeSet::from([("grow", None), ("produce_dag", Some("grow")), ("dijkstra", Some("produce_dag"))])
+        );
+    }
 }
@@ -1,5 +1,7 @@
 //! Files containing the core logic for Growing DAGs.
 
+pub mod consensus;
 pub mod cost;
 pub mod grow;
-mod path;
+pub mod path;
+pub mod seed;
@@ -1,10 +1,11 @@
 use std::{
     cmp::{Ordering, Reverse},
-    collections::{hash_map::Entry, BinaryHeap, HashMap}, hash::Hash,
+    collections::{hash_map::Entry, BinaryHeap, HashMap}, hash::Hash, ops::Add,
 };
 
 use ordered_float::OrderedFloat;
 use petgraph::{
+    graphmap::NodeTrait,
     prelude::DiGraphMap,
     visit::{EdgeRef, VisitMap, Visitable},
 };
@@ -34,25 +35,174 @@ impl<K: Ord, T: Eq> Ord for ScoreObject<K, T> {
     }
 }
 
+/// The buffers used by a Dijkstra run over the scores `K`. Passing the same scratch to
+/// every run (see `calculate_paths_in`) reuses their allocations, rather than reallocating them per run.
+#[derive(Clone, Debug)]
+pub struct DijkstraScratch<V: NodeTrait, K = OrderedFloat<f64>> {
+    /// Built by the first run, as the graph sizes it.
+    visited: Option<<DiGraphMap<V, Weight, Xxh3Builder> as Visitable>::Map>,
+    visit_next: BinaryHeap<Reverse<ScoreObject<K, V>>>,
+    scores: HashMap<V, K>,
+    targets: Vec<V>,
+}
+
+impl<V: NodeTrait, K> Default for DijkstraScratch<V, K> {
+    fn default() -> Self {
+        Self { visited: None, visit_next: BinaryHeap::new(), scores: HashMap::new(), targets: vec![] }
+    }
+}
+
+impl<V: NodeTrait, K> DijkstraScratch<V, K> {
+    /// Empties every buffer, keeping their allocations.
+    fn clear(&mut self) {
+        if let Some(visited) = &mut self.visited {
+            visited.clear();
+        }
+        self.visit_next.clear();
+        self.scores.clear();
+        self.targets.clear();
+    }
+}
+
+/// Fills `paths` with the shortest paths from `source` using Dijkstra, stopping once every target is found.
+/// Nodes in `ignore` are given a path, but are not explored past. Returns the number of edge relaxations performed.
+///
+/// Every weight must be non-negative: otherwise, the paths may silently not be the shortest (see the `negative_edge`
+/// test). Over negative weights, use `bellman_ford_paths` instead, as `produce_dag` does.
 pub fn calculate_paths<V: Clone + Copy + Eq + Ord + Hash>(
     paths: &mut Paths<V>,
     graph: &DiGraphMap<V, Weight, Xxh3Builder>,
     source: V,
     targets: &[V],
     ignore: &[V],
-) -> Result<(), NetworkIndexError> {
+) -> Result<usize, NetworkIndexError> {
+    calculate_paths_in(&mut DijkstraScratch::default(), paths, graph, source, targets, ignore)
+}
+
+/// Like `calculate_paths`, but reusing the buffers in `scratch`.
+pub fn calculate_paths_in<V: Clone + Copy + Eq + Ord + Hash>(
+    scratch: &mut DijkstraScratch<V>,
+    paths: &mut Paths<V>,
+    graph: &DiGraphMap<V, Weight, Xxh3Builder>,
+    source: V,
+    targets: &[V],
+    ignore: &[V],
+) -> Result<usize, NetworkIndexError> {
+    dijkstra(scratch, paths, graph, source, targets, ignore, OrderedFloat, |score| score.0)
+}
+
+/// Like `calculate_paths`, but scales every weight by `10^precision` and rounds it to an integer,
+/// so that path scores are summed exactly. This makes comparisons between paths independent of the order in which
+/// their weights were added, at the cost of ignoring differences in weight below `10^-precision`.
+pub fn calculate_paths_scaled<V: Clone + Copy + Eq + Ord + Hash>(
+    paths: &mut Paths<V>,
+    graph: &DiGraphMap<V, Weight, Xxh3Builder>,
+    source: V,
+    targets: &[V],
+    ignore: &[V],
+    precision: i32,
+) -> Result<usize, NetworkIndexError> {
+    calculate_paths_scaled_in(&mut DijkstraScratch::default(), paths, graph, source, targets, ignore, precision)
+}
+
+/// Like `calculate_paths_scaled`, but reusing the buffers in `scratch`.
+pub fn calculate_paths_scaled_in<V: Clone + Copy + Eq + Ord + Hash>(
+    scratch: &mut DijkstraScratch<V, i64>,
+    paths: &mut Paths<V>,
+    graph: &DiGraphMap<V, Weight, Xxh3Builder>,
+    source: V,
+    targets: &[V],
+    ignore: &[V],
+    precision: i32,
+) -> Result<usize, NetworkIndexError> {
+    let scale = 10_f64.powi(precision);
+    dijkstra(
+        scratch,
+        paths,
+        graph,
+        source,
+        targets,
+        ignore,
+        |weight| (weight * scale).round() as i64,
+        |score| score as f64 / scale,
+    )
+}
+
+/// The path from `source` to `target` through the parents in `paths` (as filled by `calculate_paths`),
+/// starting at `source`. This is just `[target]` if `target` was never reached.
+pub fn reconstruct_path<V: Copy + Eq + Hash>(paths: &Paths<V>, source: V, target: V) -> Vec<V> {
+    let mut path = vec![];
+    let mut current = Some(target);
+    while let Some(node) = current {
+        path.push(node);
+        current = paths.get(&(source, node)).and_then(|&(_, parent)| parent);
+    }
+
+    path.reverse();
+    path
+}
+
+/// The shortest path from `source` to each of the `targets` reachable from it, alongside its weight,
+/// found as in `calculate_paths` (which `ignore` is passed on to).
+pub fn shortest_paths_to<V: Clone + Copy + Eq + Ord + Hash>(
+    graph: &DiGraphMap<V, Weight, Xxh3Builder>,
+    source: V,
+    targets: &[V],
+    ignore: &[V],
+) -> Result<HashMap<V, (f64, Vec<V>)>, NetworkIndexError> {
+    let mut paths = Paths::new();
+    calculate_paths(&mut paths, graph, source, targets, ignore)?;
+
+    Ok(targets
+        .iter()
+        .filter_map(|&target| {
+            let &(weight, _) = paths.get(&(source, target))?;
+            Some((target, (weight, reconstruct_path(&paths, source, target))))
+        })
+        .collect())
+}
+
+/// The distance to every node reachable from `source`, found by running Dijkstra to completion.
+/// As with `calculate_paths`, every weight must be non-negative.
+pub fn single_source_distances<V: Clone + Copy + Eq + Ord + Hash>(
+    graph: &DiGraphMap<V, Weight, Xxh3Builder>,
+    source: V,
+) -> HashMap<V, f64> {
+    let mut paths = Paths::new();
+    // With no targets, Dijkstra never stops early.
+    dijkstra(&mut DijkstraScratch::default(), &mut paths, graph, source, &[], &[], OrderedFloat, |score| score.0)
+        .expect("Dijkstra over no targets can't fail");
+
+    paths.into_iter().map(|((_, node), (distance, _))| (node, distance)).collect()
+}
+
+/// Dijkstra over the scores `K`, which `to_score` and `from_score` convert weights to and from.
+#[allow(clippy::too_many_arguments)]
+fn dijkstra<V: Clone + Copy + Eq + Ord + Hash, K: Copy + Ord + Add<Output = K>>(
+    scratch: &mut DijkstraScratch<V, K>,
+    paths: &mut Paths<V>,
+    graph: &DiGraphMap<V, Weight, Xxh3Builder>,
+    source: V,
+    targets: &[V],
+    ignore: &[V],
+    to_score: impl Fn(f64) -> K,
+    from_score: impl Fn(K) -> f64,
+) -> Result<usize, NetworkIndexError> {
     // we reimplement this from
     // https://docs.rs/petgraph/0.8.2/src/petgraph/algo/dijkstra.rs.html#88-138
     // adjusted with the heuristics from Growing DAGs supplements.
 
-    // TODO(perf): this might be bad for perf? we will see in the benchmarks.
-    let mut targets = targets.to_vec();
+    scratch.clear();
+    let DijkstraScratch { visited, visit_next, scores, targets: remaining } = scratch;
+    let visited = visited.get_or_insert_with(|| graph.visit_map());
+    remaining.extend_from_slice(targets);
+    let targets = remaining;
 
-    let mut visited = graph.visit_map();
-    let mut visit_next = BinaryHeap::new();
+    let mut relaxations = 0;
 
     paths.insert((source, source), (0_f64, None));
-    visit_next.push(Reverse(ScoreObject(OrderedFloat(0_f64), source)));
+    scores.insert(source, to_score(0_f64));
+    visit_next.push(Reverse(ScoreObject(to_score(0_f64), source)));
     while let Some(Reverse(ScoreObject(node_score, node))) = visit_next.pop() {
         if visited.is_visited(&node) {
             continue;
@@ -61,7 +211,7 @@ pub fn calculate_paths<V: Clone + Copy + Eq + Ord + Hash>(
         if let Some(idx) = targets.iter().position(|target| *target == node) {
             targets.remove(idx);
             if targets.is_empty() {
-                return Ok(());
+                return Ok(relaxations);
             }
         }
 
@@ -75,22 +225,174 @@ pub fn calculate_paths<V: Clone + Copy + Eq + Ord + Hash>(
                 continue;
             }
 
-            let next_score = node_score + edge.weight().0;
-            match paths.entry((source, next)) {
+            let next_score = node_score + to_score(edge.weight().0);
+            match scores.entry(next) {
                 Entry::Occupied(ent) => {
-                    if next_score.0 < ent.get().0 {
-                        *ent.into_mut() = (*next_score, Some(node));
-                        visit_next.push(Reverse(ScoreObject(next_score, next)));
+                    if next_score < *ent.get() {
+                        *ent.into_mut() = next_score;
+                    } else {
+                        continue;
                     }
                 }
                 Entry::Vacant(ent) => {
-                    ent.insert((*next_score, Some(node)));
-                    visit_next.push(Reverse(ScoreObject(next_score, next)));
+                    ent.insert(next_score);
                 }
             }
+
+            paths.insert((source, next), (from_score(next_score), Some(node)));
+            visit_next.push(Reverse(ScoreObject(next_score, next)));
+            relaxations += 1;
         }
         visited.visit(node);
     }
 
-    Ok(())
+    Ok(relaxations)
+}
+
+/// A negative-weight cycle, reachable from the source, passing through the contained node.
+#[derive(Debug)]
+pub struct NegativeCycleError<V>(pub V);
+
+/// Like `calculate_paths`, but correct over negative edge weights, using Bellman-Ford.
+/// This runs in `O(VE)` (rather than Dijkstra's `O(E + V log V)`) and cannot stop early once every target
+/// is found, so it should only be used when the graph actually has negative weights.
+///
+/// Nodes in `ignore` are given a path, but are not explored past. Errors if a negative cycle is reachable
+/// from `source`, as shortest paths are then ill-defined. Returns the number of edge relaxations performed.
+pub fn bellman_ford_paths<V: Clone + Copy + Eq + Ord + Hash>(
+    paths: &mut Paths<V>,
+    graph: &DiGraphMap<V, Weight, Xxh3Builder>,
+    source: V,
+    ignore: &[V],
+) -> Result<usize, NegativeCycleError<V>> {
+    let mut relaxations = 0;
+    let mut distances: HashMap<V, f64> = HashMap::from([(source, 0_f64)]);
+    paths.insert((source, source), (0_f64, None));
+
+    // After |V| - 1 rounds of relaxation, every shortest path has been found;
+    // the |V|th round only succeeds if there is a negative cycle.
+    for round in 0..graph.node_count() {
+        let mut relaxed = false;
+
+        for (from, to, weight) in graph.all_edges() {
+            if ignore.contains(&from) {
+                continue;
+            }
+            let Some(&from_distance) = distances.get(&from) else {
+                continue;
+            };
+
+            let next_distance = from_distance + weight.0;
+            if distances.get(&to).is_none_or(|&distance| next_distance < distance) {
+                if round == graph.node_count() - 1 {
+                    return Err(NegativeCycleError(to));
+                }
+
+                distances.insert(to, next_distance);
+                paths.insert((source, to), (next_distance, Some(from)));
+                relaxations += 1;
+                relaxed = true;
+            }
+        }
+
+        if !relaxed {
+            break;
+        }
+    }
+
+    Ok(relaxations)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use either::Either;
+
+    use crate::parsing::{network::Network, weight::WeightDataFactory};
+
+    use super::*;
+
+    #[test]
+    fn negative_edge() {
+        // Dijkstra settles A at 1 before seeing B -> A, which makes it 0.
+        let graph: DiGraphMap<usize, Weight, Xxh3Builder> = DiGraphMap::from_edges([
+            (0, 1, Weight(1.0)),
+            (0, 2, Weight(2.0)),
+            (2, 1, Weight(-2.0)),
+        ]);
+
+        let mut dijkstra = Paths::new();
+        calculate_paths(&mut dijkstra, &graph, 0, &[1, 2], &[]).unwrap();
+        assert_eq!(dijkstra[&(0, 1)], (1.0, Some(0)));
+
+        let mut bellman_ford = Paths::new();
+        bellman_ford_paths(&mut bellman_ford, &graph, 0, &[]).unwrap();
+        assert_eq!(bellman_ford[&(0, 1)], (0.0, Some(2)));
+        assert_eq!(bellman_ford[&(0, 2)], (2.0, Some(0)));
+    }
+
+    #[test]
+    fn scaled_is_order_independent() {
+        // The same weights along a path, summed in opposite orders.
+        let forward: DiGraphMap<usize, Weight, Xxh3Builder> =
+            DiGraphMap::from_edges([(0, 1, Weight(0.1)), (1, 2, Weight(0.2)), (2, 3, Weight(0.3))]);
+        let backward: DiGraphMap<usize, Weight, Xxh3Builder> =
+            DiGraphMap::from_edges([(0, 1, Weight(0.3)), (1, 2, Weight(0.2)), (2, 3, Weight(0.1))]);
+
+        let score = |graph, scaled| {
+            let mut paths = Paths::new();
+            if scaled {
+                calculate_paths_scaled(&mut paths, graph, 0, &[3], &[], 6).unwrap();
+            } else {
+                calculate_paths(&mut paths, graph, 0, &[3], &[]).unwrap();
+            }
+            paths[&(0, 3)].0
+        };
+
+        assert_ne!(score(&forward, false), score(&backward, false));
+        assert_eq!(score(&forward, true), score(&backward, true));
+        assert_eq!(score(&forward, true), 0.6);
+    }
+
+    #[test]
+    fn triangle_distances() {
+        let network =
+            Network::<Weight, ()>::from_file::<WeightDataFactory>(Path::new("./tests/fixtures/triangle/interactome.txt"))
+                .unwrap();
+        let node = |name| Either::Left(network.get_node(name).unwrap());
+
+        let distances = single_source_distances(&network.graph, node("B"));
+        assert_eq!(distances, HashMap::from([(node("B"), 0.0), (node("C"), 1.0)]));
+
+        let distances = single_source_distances(&network.graph, node("A"));
+        assert_eq!(distances, HashMap::from([(node("A"), 0.0), (node("B"), 1.0), (node("C"), 1.0)]));
+    }
+
+    #[test]
+    fn triangle_shortest_paths() {
+        let network =
+            Network::<Weight, ()>::from_file::<WeightDataFactory>(Path::new("./tests/fixtures/triangle/interactome.txt"))
+                .unwrap();
+        let node = |name| Either::Left(network.get_node(name).unwrap());
+
+        let paths = shortest_paths_to(&network.graph, node("A"), &[node("C"), node("B")], &[]).unwrap();
+        assert_eq!(paths[&node("C")], (1.0, vec![node("A"), node("C")]));
+        assert_eq!(paths[&node("B")], (1.0, vec![node("A"), node("B")]));
+
+        // Ignored nodes are still reached, but not passed through, and unreachable targets are left out.
+        let paths = shortest_paths_to(&network.graph, node("B"), &[node("A"), node("C")], &[node("C")]).unwrap();
+        assert_eq!(paths, HashMap::from([(node("C"), (1.0, vec![node("B"), node("C")]))]));
+    }
+
+    #[test]
+    fn negative_cycle() {
+        let graph: DiGraphMap<usize, Weight, Xxh3Builder> = DiGraphMap::from_edges([
+            (0, 1, Weight(1.0)),
+            (1, 2, Weight(-2.0)),
+            (2, 1, Weight(1.0)),
+        ]);
+
+        assert!(bellman_ford_paths(&mut Paths::new(), &graph, 0, &[]).is_err());
+    }
 }
@@ -0,0 +1,104 @@
+//! Growing many DAGs ('seeds') against the same interactome.
+
+use std::path::Path;
+
+use bimap::BiHashMap;
+use never::Never;
+use thiserror::Error;
+
+use crate::{
+    parsing::{
+        dag::{DAGCreationError, PartialDag},
+        data::EmptyTupleDataFactory,
+        interactome::{Interactome, InteractomeAttachError},
+        network::Network,
+        weight::Weight,
+    },
+    util::{read_terminals, Terminal},
+};
+
+use super::{
+    cost::Cost,
+    grow::{grow_k, GrowError, GrowthCache, GrowthOptions, ScoredPath},
+};
+
+#[derive(Debug, Error)]
+pub enum SeedGrowthError {
+    #[error(transparent)]
+    InteractomeAttachError(#[from] InteractomeAttachError),
+    #[error(transparent)]
+    DAGCreationError(#[from] DAGCreationError),
+    #[error(transparent)]
+    GrowError(#[from] GrowError),
+}
+
+/// A grown DAG, along with the paths which were grown onto it.
+pub type GrownSeed = (PartialDag<()>, Vec<ScoredPath>);
+
+/// An initial DAG along with the (possibly weighted) sources and targets to grow it between.
+/// Its DAG must share the id_map of the interactome it is grown against.
+pub struct Seed {
+    pub dag: Network<(), Never>,
+    pub sources: Vec<Terminal>,
+    pub targets: Vec<Terminal>,
+}
+
+impl Seed {
+    /// Reads a seed from a folder containing a dag.txt, sources.txt, and targets.txt,
+    /// resolving the DAG's nodes through `id_map`. `skip_header` applies to the dag.txt.
+    pub fn from_folder(
+        path: &Path,
+        id_map: &BiHashMap<String, usize>,
+        skip_header: bool,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            dag: Network::from_file_using_id_map::<EmptyTupleDataFactory>(
+                &path.join("dag.txt"),
+                id_map,
+                skip_header,
+            )?,
+            sources: read_terminals(&path.join("sources.txt"))?,
+            targets: read_terminals(&path.join("targets.txt"))?,
+        })
+    }
+}
+
+/// Grows every seed up to `k` times under `options` against a single, already-parsed interactome `network`,
+/// returning each grown DAG alongside its grown paths.
+///
+/// Each seed attaches its own sources and targets to a copy of `network`, so the (expensive)
+/// parsing step only happens once. Its super-edges are weighted by `super_edge_weight`, if given,
+/// and then by its terminals' weights (see `Interactome::weight_super_edges` and `Interactome::weight_terminals`).
+///
+/// Seeds are grown as if independently: every seed starts from a fresh clone of `cost`, so that stateful costs
+/// such as `RecencyCost` don't carry over between seeds, and a single cache is reset to each seed's interactome.
+pub fn grow_seeds<C: Cost + Clone>(
+    network: &Network<Weight, Never>,
+    seeds: Vec<Seed>,
+    cost: &C,
+    options: &GrowthOptions,
+    super_edge_weight: Option<Weight>,
+    k: usize,
+) -> Result<Vec<GrownSeed>, SeedGrowthError> {
+    let names = |terminals: &[Terminal]| terminals.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>();
+    let mut cache = GrowthCache::lazy();
+    cache.options = options.clone();
+
+    seeds
+        .into_iter()
+        .map(|seed| {
+            let (sources, targets) = (names(&seed.sources), names(&seed.targets));
+            let mut interactome = Interactome::attach_sources_and_targets(network.clone(), &sources, &targets, true)?;
+            if let Some(weight) = super_edge_weight {
+                interactome.weight_super_edges(weight);
+            }
+            interactome.weight_terminals(&seed.sources, &seed.targets);
+            let mut dag = PartialDag::new(seed.dag, &sources, &targets)?;
+
+            cache.reset(&interactome);
+            let paths = grow_k(&interactome, &mut dag, &mut cache, &mut cost.clone(), k, None)?;
+
+            Ok((dag, paths))
+        })
+        .collect()
+}
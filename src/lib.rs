@@ -1,3 +1,13 @@
+/// Enters a `tracing` span (`$level` being `info`, `debug`, ...) for the rest of the enclosing block,
+/// if built with the `tracing` feature. Otherwise, this does nothing.
+#[macro_export]
+macro_rules! enter_span {
+    ($level:ident, $name:literal $(, $($fields:tt)*)?) => {
+        #[cfg(feature = "tracing")]
+        let _span = ::tracing::span!(::tracing::Level::$level, $name $(, $($fields)*)?).entered();
+    };
+}
+
 pub mod alg;
 pub mod parsing;
 pub mod util;
@@ -1,40 +1,270 @@
-use std::path::PathBuf;
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use anyhow::anyhow;
+use bimap::BiHashMap;
+use either::Either;
 
-use growing_dags::parsing::interactome::Interactome;
+use growing_dags::parsing::interactome::{Interactome, InteractomeAttachError, SuperNode};
 use growing_dags::parsing::{
-    dag::PartialDag,
-    data::EmptyTupleDataFactory,
-    weight::{LogWeightDataFactory, WeightDataFactory},
+    dag::{DAGCreationError, PartialDag},
+    data::{DataFactory, EmptyTupleDataFactory},
+    weight::{
+        ColumnWeightDataFactory, InvertedWeightDataFactory, LogWeightDataFactory, Weight, WeightDataFactory,
+        LOG_WEIGHT_FLOOR,
+    },
 };
 use growing_dags::{
+    enter_span,
     alg::{
-        cost::EdgeCost,
-        grow::{grow, GrowthCache},
+        consensus::consensus,
+        cost::{CombinedCost, Cost, CostRegistry, LengthPenaltyCost},
+        grow::{grow_k, grow_k_parallel, GrowError, GrowResult, GrowthCache, GrowthOptions, StopCondition, TieBreak},
+        seed::{grow_seeds, Seed, SeedGrowthError},
     },
-    util::read_lines,
+    util::{read_terminals, Terminal},
 };
 
-use clap::{ArgAction, Parser, Subcommand};
-use growing_dags::parsing::network::Network;
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use growing_dags::parsing::network::{
+    Network, NetworkIndexError, NetworkParsingError, ParseOptions, UnnamedNodeError, METADATA_PREFIX,
+};
 use log::*;
 use never::Never;
 
 extern crate pretty_env_logger;
 
+const EXIT_CODES_HELP: &str = "Exit codes:
+  0  Success
+  1  Unknown error
+  2  Invalid command-line usage
+  3  An input file could not be read
+  4  The interactome has a malformed line or weight, is empty, or uses a reserved gene name
+  5  The DAG contains a node which is not in the interactome
+  6  A source does not exist in the interactome
+  7  A target does not exist in the interactome
+  8  The DAG has cycles
+  9  A node could not be found in the network, or has no name
+  10 The interactome has a negative-weight cycle
+  11 The interactome has a negative weight, without --bellman-ford";
+
 #[derive(Parser)]
+#[command(after_help = EXIT_CODES_HELP)]
 struct Cli {
+    #[command(flatten)]
+    options: Options,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Args)]
+struct Options {
+    /// How to turn interactome weights into costs, where lower is better.
+    #[arg(long, value_enum, default_value_t = Transform::Log)]
+    transform: Transform,
+
     /// Whether to _not_ transform all the weights if they currently represent "higher = better."
     /// If your interactome already comes with weights that represent "lower = better,"
-    /// use this option.
-    #[arg(short, long, action=ArgAction::SetFalse)]
+    /// use this option. Shorthand for `--transform identity`.
+    #[arg(short, long, action=ArgAction::SetFalse, conflicts_with = "transform")]
     no_log_transform: bool,
 
+    /// Give the edges out of the super-source and into the super-target this (untransformed) weight,
+    /// rather than leaving them free. Under the log transform, `1` puts them on the same scale as a certain
+    /// interaction. Weights given alongside sources and targets take precedence.
+    #[arg(long)]
+    super_edge_weight: Option<f64>,
+
     /// The number of times to grow a new DAG.
     #[arg(short, long)]
     k: usize,
 
-    #[command(subcommand)]
-    command: Commands,
+    /// When to stop growing before all `-k` iterations have run (besides running out of paths).
+    #[arg(long, value_enum, default_value_t = Until::Iterations)]
+    until: Until,
+
+    /// Stop growing before the DAG would have more than this many edges (leaving out those to and from
+    /// the super-source and super-target). The path which would exceed it is not added.
+    #[arg(long)]
+    max_edges: Option<usize>,
+
+    /// How to order paths with the same cost, before falling back to their length.
+    #[arg(long, value_enum, default_value_t = TieBreakArg::Length)]
+    tie_break: TieBreakArg,
+
+    /// Read interactome weights from this (1-indexed) column instead of the third, ignoring every other column.
+    #[arg(long, value_parser = clap::value_parser!(u64).range(3..))]
+    weight_column: Option<u64>,
+
+    /// The name of the cost function to rank paths by: one of `edge`, `node`, or `path`.
+    #[arg(long, default_value = "edge")]
+    cost: String,
+
+    /// A tab-separated `gene\tweight` file of node weights, where lower is better.
+    /// Genes which are not in the file have a weight of 0.
+    #[arg(long)]
+    node_weights: Option<PathBuf>,
+
+    /// Rank paths by `alpha * edge cost + (1 - alpha) * node cost`, using the `--node-weights`.
+    #[arg(long, requires = "node_weights", conflicts_with = "cost", value_parser = parse_alpha)]
+    node_weight_alpha: Option<f64>,
+
+    /// Add this much to the cost of a path for every edge it adds, discouraging long detours.
+    #[arg(long)]
+    length_penalty: Option<f64>,
+
+    /// Keep only this many of the cheapest incoming edges of every gene in the interactome, after weights are
+    /// transformed. This shrinks dense interactomes a lot, at the risk of dropping weaker interactions which matter.
+    #[arg(long)]
+    keep_top_k: Option<usize>,
+
+    /// Allow negative interactome weights (after the transform), finding paths with the much slower Bellman-Ford
+    /// rather than Dijkstra. Without this, a negative weight is an error.
+    #[arg(long)]
+    bellman_ford: bool,
+
+    /// Parse the interactome across multiple threads. Useful for very large interactomes.
+    #[arg(long)]
+    parallel_parse: bool,
+
+    /// A tab-separated `gene\tid` file of node ids to parse the interactome over, so that genes keep the same id
+    /// across runs. Genes which are not in the file are given ids after the largest one in it.
+    #[arg(long, conflicts_with = "parallel_parse")]
+    id_map: Option<PathBuf>,
+
+    /// Score candidate paths across this many threads, which helps with expensive costs such as `path`.
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+    threads: Option<u16>,
+
+    /// Skip the first (non-comment) line of the interactome and DAG, e.g. a `source\ttarget\tweight` header.
+    #[arg(long)]
+    has_header: bool,
+
+    /// Grow from whichever sources and targets are in the interactome, rather than failing on any missing one.
+    #[arg(long)]
+    allow_missing: bool,
+
+    /// After every iteration, write the grown DAG's edges to `iteration_{i}.tsv` in this directory,
+    /// which is created if it is missing.
+    #[arg(long)]
+    output_dir: Option<PathBuf>,
+
+    /// After growing, write the DAG's edges to `dag_weights.tsv` in the `--output-dir`, as
+    /// `a\tb\tcost\traw_weight`: their (transformed) cost alongside their weight in the interactome file.
+    /// Edges which are not in the interactome have a cost and weight of `NA`.
+    #[arg(long, requires = "output_dir")]
+    dag_weights: bool,
+
+    /// Print, for each node, whether it is reachable from the sources and whether it can reach the targets
+    /// to stderr, before growing.
+    #[arg(long)]
+    reachability: bool,
+
+    /// Instead of printing grown paths, grow the DAG this many times with different tie-breaks between
+    /// equal-cost paths, printing the edges which appear in at least `--consensus-fraction` of the runs.
+    /// There are no iterations to snapshot, so this can't be combined with `--output-dir`.
+    #[arg(long)]
+    consensus: Option<usize>,
+
+    /// The fraction of consensus runs an edge must appear in to be printed.
+    #[arg(long, default_value_t = 0.5, requires = "consensus")]
+    consensus_fraction: f64,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Transform {
+    /// A negative log transform, for "higher = better" weights such as probabilities.
+    Log,
+    /// `1 - w`, for "higher = better" similarities in [0, 1].
+    Invert,
+    /// Use the weights as-is, for "lower = better" weights.
+    Identity,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Until {
+    /// Run every iteration.
+    Iterations,
+    /// Stop once every target is connected to the sources through the DAG.
+    AllTargetsConnected,
+}
+
+impl From<Until> for StopCondition {
+    fn from(until: Until) -> Self {
+        match until {
+            Until::Iterations => StopCondition::Iterations,
+            Until::AllTargetsConnected => StopCondition::AllTargetsConnected,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TieBreakArg {
+    /// Prefer shorter paths.
+    Length,
+    /// Prefer the path whose most expensive new edge is cheapest.
+    Bottleneck,
+}
+
+impl From<TieBreakArg> for TieBreak {
+    fn from(tie_break: TieBreakArg) -> Self {
+        match tie_break {
+            TieBreakArg::Length => TieBreak::Length,
+            TieBreakArg::Bottleneck => TieBreak::Bottleneck,
+        }
+    }
+}
+
+impl Options {
+    fn transform(&self) -> Transform {
+        if self.no_log_transform {
+            self.transform
+        } else {
+            Transform::Identity
+        }
+    }
+}
+
+impl Transform {
+    /// Transforms a single interactome weight.
+    fn apply(self, weight: f64) -> anyhow::Result<Weight> {
+        let strs = vec![weight.to_string()];
+        match self {
+            Transform::Log => LogWeightDataFactory(WeightDataFactory).from_strs(0, strs),
+            Transform::Invert => InvertedWeightDataFactory(WeightDataFactory).from_strs(0, strs),
+            Transform::Identity => WeightDataFactory.from_strs(0, strs),
+        }
+    }
+}
+
+/// Records how a run was configured at the top of its output, as `#@key value` metadata lines
+/// (see `METADATA_PREFIX`), so that shared results say how their weights were turned into costs.
+/// `cost_header` describes the cost which was actually chosen (see `run`).
+fn write_header(out: &mut impl Write, options: &Options, cost_header: &[(&str, String)]) -> io::Result<()> {
+    let transform = options.transform();
+    let name = transform.to_possible_value().expect("no transform is skipped");
+    writeln!(out, "{METADATA_PREFIX}transform {}", name.get_name())?;
+    if let Transform::Log = transform {
+        writeln!(out, "{METADATA_PREFIX}log_weight_floor {LOG_WEIGHT_FLOOR:e}")?;
+    }
+    for (key, value) in cost_header {
+        writeln!(out, "{METADATA_PREFIX}{key} {value}")?;
+    }
+    writeln!(out, "{METADATA_PREFIX}k {}", options.k)
+}
+
+fn parse_alpha(alpha: &str) -> Result<f64, String> {
+    let alpha = alpha.parse::<f64>().map_err(|err| err.to_string())?;
+    if !(0.0..=1.0).contains(&alpha) {
+        return Err(format!("{alpha} is not between 0 and 1"));
+    }
+
+    Ok(alpha)
 }
 
 #[derive(Subcommand)]
@@ -46,90 +276,602 @@ enum Commands {
         interactome: PathBuf,
         /// The tab-separated initial DAG, which is usually a known gold-standard pathway in the above PPI.
         dag: PathBuf,
-        /// The sources Growing DAGs should try to start at.
-        sources: PathBuf,
-        /// The targets Growing DAGs should try to end at.
-        targets: PathBuf,
+        /// The sources Growing DAGs should try to start at, one per line, optionally followed by a
+        /// tab-separated weight for the edge from the super-source.
+        #[arg(required_unless_present = "source")]
+        sources: Option<PathBuf>,
+        /// The targets Growing DAGs should try to end at, in the same format as the sources.
+        #[arg(required_unless_present = "target")]
+        targets: Option<PathBuf>,
+        /// A source to start at, in place of the sources file. Repeat for more sources.
+        #[arg(long = "source", value_name = "GENE", requires = "target", conflicts_with_all = ["sources", "targets"])]
+        source: Vec<String>,
+        /// A target to end at, in place of the targets file. Repeat for more targets.
+        #[arg(long = "target", value_name = "GENE", requires = "source", conflicts_with_all = ["sources", "targets"])]
+        target: Vec<String>,
     },
     /// Specify input through a single, containing folder.
     Folder {
         /// The folder containing an interactome.txt, dag.txt, sources.txt, and targets.txt
         path: PathBuf
+    },
+    /// Grow a DAG in every subfolder of a root folder, each laid out as for `folder`, printing a summary line
+    /// of `folder\titerations\ttotal cost` per subfolder. Each subfolder's grown paths are written to
+    /// its `grown_paths.tsv` (or `<output-dir>/<subfolder>/grown_paths.tsv`, with `--output-dir`), and
+    /// subfolders missing any of the four files are skipped. A subfolder which fails to grow gets `NA` in
+    /// its summary line, without stopping the others, and the run then fails once every subfolder is done.
+    BatchFolder {
+        /// The folder containing every subfolder to grow in.
+        root: PathBuf,
+    },
+    /// Grow a DAG for each of many seed folders against one interactome, which is only parsed once.
+    Seeds {
+        /// The tab-separated interactome, in the same format as for `files`.
+        interactome: PathBuf,
+        /// The folders each containing a dag.txt, sources.txt, and targets.txt
+        #[arg(required = true)]
+        seeds: Vec<PathBuf>,
     }
 }
 
-fn main() -> anyhow::Result<()> {
+fn parsing_exit_code(err: &NetworkParsingError) -> u8 {
+    match err {
+        NetworkParsingError::Misc(_) => 3,
+        NetworkParsingError::ParseDataError(_)
+        | NetworkParsingError::InvalidSizeError(..)
+        | NetworkParsingError::DuplicateEdge(..) => 4,
+        NetworkParsingError::FactoryOut(..) => 5,
+    }
+}
+
+fn attach_exit_code(err: &InteractomeAttachError) -> u8 {
+    match err {
+        InteractomeAttachError::SourceNotExists(_) => 6,
+        InteractomeAttachError::TargetNotExists(_) => 7,
+        InteractomeAttachError::EmptyNetwork | InteractomeAttachError::ReservedName(_) => 4,
+    }
+}
+
+fn dag_exit_code(err: &DAGCreationError) -> u8 {
+    match err {
+        DAGCreationError::InteractomeAttachError(err) => attach_exit_code(err),
+        DAGCreationError::NetworkIndexError(_) => 5,
+        DAGCreationError::IsCyclic => 8,
+    }
+}
+
+fn grow_exit_code(err: &GrowError) -> u8 {
+    match err {
+        GrowError::NetworkIndexError(_) => 9,
+        GrowError::NegativeCycle(_) => 10,
+        GrowError::NegativeWeight(_) => 11,
+    }
+}
+
+/// Maps an error to its stable exit code, as documented in `EXIT_CODES_HELP`.
+fn exit_code(err: &anyhow::Error) -> u8 {
+    for cause in err.chain() {
+        if let Some(err) = cause.downcast_ref::<NetworkParsingError>() {
+            return parsing_exit_code(err);
+        }
+
+        if let Some(err) = cause.downcast_ref::<InteractomeAttachError>() {
+            return attach_exit_code(err);
+        }
+
+        if let Some(err) = cause.downcast_ref::<DAGCreationError>() {
+            return dag_exit_code(err);
+        }
+
+        if let Some(err) = cause.downcast_ref::<SeedGrowthError>() {
+            return match err {
+                SeedGrowthError::InteractomeAttachError(err) => attach_exit_code(err),
+                SeedGrowthError::DAGCreationError(err) => dag_exit_code(err),
+                SeedGrowthError::GrowError(err) => grow_exit_code(err),
+            };
+        }
+
+        if let Some(err) = cause.downcast_ref::<GrowError>() {
+            return grow_exit_code(err);
+        }
+
+        if cause.downcast_ref::<NetworkIndexError>().is_some() || cause.downcast_ref::<UnnamedNodeError>().is_some() {
+            return 9;
+        }
+
+        if cause.downcast_ref::<io::Error>().is_some() {
+            return 3;
+        }
+    }
+
+    1
+}
+
+fn main() -> ExitCode {
     pretty_env_logger::init_timed();
 
     let cli = Cli::parse();
 
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {err:?}");
+            ExitCode::from(exit_code(&err))
+        }
+    }
+}
+
+/// Chooses the cost, along with the `#@key value` metadata describing it (see `write_header`), and runs with it.
+fn run(cli: Cli) -> anyhow::Result<()> {
+    if let Some(alpha) = cli.options.node_weight_alpha {
+        let cost_header = vec![("cost", "combined".to_string()), ("node_weight_alpha", alpha.to_string())];
+        return run_with_penalty(cli, CombinedCost::new(alpha), cost_header);
+    }
+
+    let registry = CostRegistry::default();
+    let cost = registry.resolve(&cli.options.cost).ok_or_else(|| {
+        anyhow!(
+            "Unknown cost '{}'. Available costs: {}",
+            cli.options.cost,
+            registry.names().collect::<Vec<_>>().join(", ")
+        )
+    })?;
+    let cost_header = vec![("cost", cli.options.cost.clone())];
+    run_with_penalty(cli, cost, cost_header)
+}
+
+fn run_with_penalty<C: Cost + Clone + Send + Sync>(
+    cli: Cli,
+    cost: C,
+    mut cost_header: Vec<(&str, String)>,
+) -> anyhow::Result<()> {
+    match cli.options.length_penalty {
+        None => run_with(cli, cost, &cost_header),
+        Some(lambda) => {
+            cost_header.push(("length_penalty", lambda.to_string()));
+            run_with(cli, LengthPenaltyCost::new(cost, lambda), &cost_header)
+        }
+    }
+}
+
+fn run_with<C: Cost + Clone + Send + Sync>(cli: Cli, cost: C, cost_header: &[(&str, String)]) -> anyhow::Result<()> {
+    let options = &cli.options;
+    let output_dir = options.output_dir.as_deref();
     match cli.command {
         Commands::Folder { path } => {
-            let interactome = path.join("interactome.txt");
-            let dag = path.join("dag.txt");
-            let sources = path.join("sources.txt");
-            let targets = path.join("targets.txt");
-            handle_files(interactome, dag, sources, targets, cli.no_log_transform, cli.k)
+            let [interactome, dag, sources, targets] = FOLDER_FILES.map(|file| path.join(file));
+            let (sources, targets) = (Terminals::File(sources), Terminals::File(targets));
+            let out = &mut io::stdout().lock();
+            handle_files(interactome, dag, sources, targets, options, output_dir, cost, cost_header, out).map(|_| ())
         },
-        Commands::Files { interactome, dag, sources, targets } => {
-            handle_files(interactome, dag, sources, targets, cli.no_log_transform, cli.k)
+        Commands::Files { interactome, dag, sources, targets, source, target } => {
+            // clap makes sure that either both files or both inline terminals were given.
+            let (sources, targets) = match (sources, targets) {
+                (Some(sources), Some(targets)) => (Terminals::File(sources), Terminals::File(targets)),
+                _ => (Terminals::Inline(source), Terminals::Inline(target)),
+            };
+            let out = &mut io::stdout().lock();
+            handle_files(interactome, dag, sources, targets, options, output_dir, cost, cost_header, out).map(|_| ())
+        },
+        Commands::BatchFolder { root } => handle_batch_folder(&root, options, cost, cost_header),
+        Commands::Seeds { interactome, seeds } => handle_seeds(interactome, seeds, &cli.options, cost, cost_header),
+    }
+}
+
+/// Sources or targets, either read from a file or given on the command line.
+enum Terminals {
+    File(PathBuf),
+    Inline(Vec<String>),
+}
+
+impl Terminals {
+    fn read(&self) -> anyhow::Result<Vec<Terminal>> {
+        match self {
+            Terminals::File(path) => read_terminals(path),
+            Terminals::Inline(names) => Ok(names.iter().map(|name| (name.clone(), None)).collect()),
         }
     }
 }
 
-fn handle_files(
-    interactome: PathBuf,
-    dag: PathBuf,
-    sources: PathBuf,
-    targets: PathBuf,
-    no_log_transform: bool,
-    k: usize,
+/// The files a folder passed to `folder` (or each subfolder of `batch-folder`) must contain.
+const FOLDER_FILES: [&str; 4] = ["interactome.txt", "dag.txt", "sources.txt", "targets.txt"];
+
+fn handle_batch_folder<C: Cost + Clone + Send + Sync>(
+    root: &Path,
+    options: &Options,
+    cost: C,
+    cost_header: &[(&str, String)],
 ) -> anyhow::Result<()> {
-    info!("Reading sources & targets...");
-    let sources = read_lines(&sources)?;
-    let targets = read_lines(&targets)?;
+    if options.consensus.is_some() {
+        return Err(anyhow!("--consensus is not supported by batch-folder"));
+    }
+
+    let mut folders = fs::read_dir(root)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    folders.retain(|folder| folder.is_dir());
+    folders.sort();
+
+    let mut failed = vec![];
+    for folder in folders {
+        let name = folder.file_name().unwrap().to_string_lossy().to_string();
+        let files = FOLDER_FILES.map(|file| folder.join(file));
+        if let Some(missing) = files.iter().find(|file| !file.is_file()) {
+            warn!("Skipping {name}, as it has no {}.", missing.file_name().unwrap().to_string_lossy());
+            continue;
+        }
+
+        info!("Growing in {name}...");
+        let output_dir = options.output_dir.as_ref().map(|output_dir| output_dir.join(&name));
+        match grow_in_folder(&folder, files, options, output_dir.as_deref(), cost.clone(), cost_header) {
+            Ok(summary) => println!("{name}\t{}\t{}", summary.iterations, summary.total_cost),
+            Err(err) => {
+                error!("Failed to grow in {name}: {err:#}");
+                println!("{name}\tNA\tNA");
+                failed.push(name);
+            }
+        }
+    }
+
+    if !failed.is_empty() {
+        return Err(anyhow!("Failed to grow in {} folder(s): {}", failed.len(), failed.join(", ")));
+    }
 
+    Ok(())
+}
+
+/// Grows in one subfolder of `batch-folder`, writing its grown paths to `grown_paths.tsv` in `output_dir`,
+/// or in the subfolder itself without one.
+fn grow_in_folder<C: Cost + Clone + Send + Sync>(
+    folder: &Path,
+    [interactome, dag, sources, targets]: [PathBuf; 4],
+    options: &Options,
+    output_dir: Option<&Path>,
+    cost: C,
+    cost_header: &[(&str, String)],
+) -> anyhow::Result<GrowSummary> {
+    if let Some(output_dir) = output_dir {
+        fs::create_dir_all(output_dir)?;
+    }
+    let paths = output_dir.unwrap_or(folder).join("grown_paths.tsv");
+
+    let mut out = io::BufWriter::new(fs::File::create(paths)?);
+    let (sources, targets) = (Terminals::File(sources), Terminals::File(targets));
+    let summary = handle_files(interactome, dag, sources, targets, options, output_dir, cost, cost_header, &mut out)?;
+    out.flush()?;
+
+    Ok(summary)
+}
+
+fn read_network(interactome: &Path, options: &Options) -> anyhow::Result<Network<Weight, Never>> {
     info!("Caching interactome...");
-    let network = if no_log_transform {
-        Network::from_file::<LogWeightDataFactory>(&interactome)?
-    } else {
-        Network::from_file::<WeightDataFactory>(&interactome)?
+    enter_span!(INFO, "parse", path = %interactome.display());
+
+    let mut network = parse_network(interactome, options, options.transform())?;
+    for (key, value) in &network.metadata {
+        info!("Interactome metadata: {key} = {value}");
+    }
+    // Negative weights are fine under Bellman-Ford, but shortest paths are ill-defined around a negative cycle.
+    if network.has_negative_weights() {
+        if !options.bellman_ford {
+            let (a, b, weight) = network.graph.all_edges().find(|(_, _, weight)| weight.0 < 0.0).unwrap();
+            let [a, b] = [a, b].map(|node| network.display_name(node.left().unwrap()));
+            let err = GrowError::NegativeWeight(format!("{a} -> {b} ({})", weight.0));
+            return Err(anyhow::Error::new(err).context("Pass --bellman-ford to allow negative weights"));
+        }
+        if let Some(cycle) = network.find_negative_cycle() {
+            let cycle = cycle
+                .iter()
+                .chain(cycle.first())
+                .map(|node| network.display_name(node.left().unwrap()))
+                .collect::<Vec<_>>();
+            return Err(GrowError::NegativeCycle(cycle.join(" -> ")).into());
+        }
+
+        info!("The interactome has negative weights, so paths will be found with Bellman-Ford.");
+    }
+
+    if let Some(k) = options.keep_top_k {
+        let removed = network.sparsify_incoming(k);
+        info!("Sparsified the interactome to the top {k} incoming edges of each gene, removing {removed} edges.");
+    }
+    if let Some(node_weights) = &options.node_weights {
+        info!("Reading node weights...");
+        network.attach_node_weights(node_weights)?;
+    }
+
+    Ok(network)
+}
+
+/// Parses the interactome's weights from the `--weight-column` under `transform`, without any further processing.
+fn parse_network(
+    interactome: &Path,
+    options: &Options,
+    transform: Transform,
+) -> anyhow::Result<Network<Weight, Never>> {
+    match options.weight_column {
+        None => read_network_with(interactome, options, transform, WeightDataFactory),
+        Some(column) => {
+            let column = usize::try_from(column)?;
+            read_network_with(interactome, options, transform, ColumnWeightDataFactory::new(column))
+        }
+    }
+}
+
+fn read_network_with<F: DataFactory<Weight> + Sync>(
+    interactome: &Path,
+    options: &Options,
+    transform: Transform,
+    factory: F,
+) -> anyhow::Result<Network<Weight, Never>> {
+    match transform {
+        Transform::Log => read_network_over(interactome, options, &LogWeightDataFactory(factory)),
+        Transform::Invert => read_network_over(interactome, options, &InvertedWeightDataFactory(factory)),
+        Transform::Identity => read_network_over(interactome, options, &factory),
+    }
+}
+
+fn read_network_over<F: DataFactory<Weight> + Sync>(
+    interactome: &Path,
+    options: &Options,
+    factory: &F,
+) -> anyhow::Result<Network<Weight, Never>> {
+    let parse_options = ParseOptions { skip_header: options.has_header, ..ParseOptions::default() };
+    let id_map = match &options.id_map {
+        Some(path) => Network::<Weight, Never>::load_id_map(path)?,
+        None => BiHashMap::new(),
     };
 
+    Ok(if options.parallel_parse {
+        Network::from_file_parallel_with_options(interactome, factory, id_map, |_, idx| Some(idx), parse_options)?
+    } else {
+        Network::from_file_with_options(interactome, factory, id_map, |_, idx| Some(idx), parse_options)?
+    })
+}
+
+/// Writes the edges of `dag` as `a\tb\tcost\traw_weight`, sorted by name, where costs come from `interactome`
+/// and raw weights from `raw`, the untransformed interactome.
+fn write_dag_weights(
+    path: &Path,
+    interactome: &Interactome<Weight>,
+    raw: &Network<Weight, Never>,
+    dag: &PartialDag<()>,
+) -> anyhow::Result<()> {
+    let format = |weight: Option<&Weight>| weight.map_or("NA".to_string(), |weight| weight.0.to_string());
+
+    let mut lines = dag
+        .0
+        .inner_network
+        .to_edge_list()
+        .lines()
+        .map(|line| {
+            let (a, b) = line.split_once('\t').unwrap();
+            let cost = interactome.inner_network.edge_weight_by_name(a, b).ok().flatten();
+            let weight = raw.edge_weight_by_name(a, b).ok().flatten();
+            format!("{a}\t{b}\t{}\t{}\n", format(cost), format(weight))
+        })
+        .collect::<Vec<_>>();
+    lines.sort();
+
+    fs::write(path, lines.concat())?;
+    Ok(())
+}
+
+/// Formats a grown path as its `|`-separated gene names, skipping super-nodes.
+fn format_path<E: Default + Clone>(
+    interactome: &Interactome<E>,
+    path: &[Either<usize, SuperNode>],
+) -> Result<String, UnnamedNodeError> {
+    Ok(interactome.strip_super_and_name(path)?.join("|"))
+}
+
+fn handle_seeds<C: Cost + Clone>(
+    interactome: PathBuf,
+    seeds: Vec<PathBuf>,
+    options: &Options,
+    cost: C,
+    cost_header: &[(&str, String)],
+) -> anyhow::Result<()> {
+    if options.consensus.is_some() || options.output_dir.is_some() {
+        return Err(anyhow!("--consensus and --output-dir are not supported by seeds"));
+    }
+
+    let network = read_network(&interactome, options)?;
+
+    info!("Reading seeds...");
+    let seed_names = seeds.iter().map(|seed| seed.display().to_string()).collect::<Vec<_>>();
+    let seeds = seeds
+        .iter()
+        .map(|seed| Seed::from_folder(seed, &network.id_map, options.has_header))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let super_edge_weight = options.super_edge_weight.map(|weight| options.transform().apply(weight)).transpose()?;
+    let grown = grow_seeds(&network, seeds, &cost, &growth_options(options), super_edge_weight, options.k)?;
+
+    write_header(&mut io::stdout().lock(), options, cost_header)?;
+    // Every grown path's genes are named in its grown DAG.
+    for (seed_name, (dag, paths)) in seed_names.iter().zip(grown) {
+        for (i, (weight, path)) in paths.iter().enumerate() {
+            println!("{seed_name}\t{}\t{weight}\t{}", i + 1, format_path(&dag.0, path)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// The `GrowthOptions` set by `--until`, `--tie-break`, and `--max-edges`.
+fn growth_options(options: &Options) -> GrowthOptions {
+    GrowthOptions {
+        until: options.until.into(),
+        tie_break: options.tie_break.into(),
+        max_edges: options.max_edges,
+        ..Default::default()
+    }
+}
+
+/// How far `handle_files` grew its DAG.
+#[derive(Default)]
+struct GrowSummary {
+    iterations: usize,
+    total_cost: f64,
+}
+
+/// Writes the `i`th path grown by `handle_files` to `out`, and snapshots the DAG it was added to into `output_dir`.
+fn write_grown(
+    out: &mut impl Write,
+    interactome: &Interactome<Weight>,
+    output_dir: Option<&Path>,
+    i: usize,
+    grown: &GrowResult,
+    dag: &PartialDag<()>,
+) -> anyhow::Result<()> {
+    writeln!(out, "{i}\t{}\t{}", grown.weight, format_path(interactome, &grown.path)?)?;
+    if let Some(output_dir) = output_dir {
+        fs::write(output_dir.join(format!("iteration_{i}.tsv")), dag.0.inner_network.to_edge_list())?;
+    }
+
+    Ok(())
+}
+
+/// Grows a DAG from an interactome, a DAG, and its terminals, writing each grown path to `out`
+/// (or, with `--consensus`, the consensus edges) and snapshots of the DAG to `output_dir`.
+#[allow(clippy::too_many_arguments)]
+fn handle_files<C: Cost + Clone + Send + Sync>(
+    interactome: PathBuf,
+    dag: PathBuf,
+    sources: Terminals,
+    targets: Terminals,
+    options: &Options,
+    output_dir: Option<&Path>,
+    mut cost: C,
+    cost_header: &[(&str, String)],
+    out: &mut impl Write,
+) -> anyhow::Result<GrowSummary> {
+    // Consensus runs only print the edges they agree on, so there are no iterations to snapshot.
+    if options.consensus.is_some() && output_dir.is_some() {
+        return Err(anyhow!("--output-dir is not supported by --consensus"));
+    }
+
+    info!("Reading sources & targets...");
+    let weighted_sources = sources.read()?;
+    let weighted_targets = targets.read()?;
+    let sources = weighted_sources.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>();
+    let targets = weighted_targets.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>();
+
+    let interactome_path = interactome;
+    let network = read_network(&interactome_path, options)?;
+
     info!("Preprocessing interactome...");
-    let interactome = Interactome::attach_sources_and_targets(network, &sources, &targets, true)?;
+    let (interactome, coverage) = {
+        enter_span!(INFO, "attach", nodes = network.node_count());
+        let (mut interactome, coverage) = Interactome::attach_with_coverage(network, &sources, &targets)?;
+        if let Some(weight) = options.super_edge_weight {
+            interactome.weight_super_edges(options.transform().apply(weight)?);
+        }
+        interactome.weight_terminals(&weighted_sources, &weighted_targets);
+        (interactome, coverage)
+    };
+    eprintln!("{coverage}");
+    if let Some(missing) = coverage.dropped.first().filter(|_| !options.allow_missing) {
+        return Err(if sources.contains(missing) {
+            InteractomeAttachError::SourceNotExists(missing.clone())
+        } else {
+            InteractomeAttachError::TargetNotExists(missing.clone())
+        }
+        .into());
+    }
+
+    if options.reachability {
+        for (node, from_sources, to_targets) in interactome.reachability_table() {
+            eprintln!("{node}\t{from_sources}\t{to_targets}");
+        }
+    }
 
     let mut dag = PartialDag::new(
         Network::<(), Never>::from_file_using_id_map::<EmptyTupleDataFactory>(
             &dag,
             &interactome.inner_network.id_map,
+            options.has_header,
         )?,
         &sources,
         &targets,
     )?;
 
+    let growth_options = growth_options(options);
+    let pool = match options.threads {
+        Some(threads) => Some(rayon::ThreadPoolBuilder::new().num_threads(threads.into()).build()?),
+        None => None,
+    };
+
+    write_header(out, options, cost_header)?;
+    if let Some(runs) = options.consensus {
+        info!("Growing DAGs over {runs} consensus runs...");
+        let consensus = || {
+            consensus(&interactome, &dag, &cost, &growth_options, options.k, runs, options.consensus_fraction)
+        };
+        let edges = match &pool {
+            Some(pool) => pool.install(consensus)?,
+            None => consensus()?,
+        };
+        for (a, b, frequency) in edges {
+            writeln!(out, "{a}\t{b}\t{frequency}")?;
+        }
+
+        return Ok(GrowSummary::default());
+    }
+
     info!("Preparing cache...");
-    let inner_interactome = interactome.clone();
-
-    for i in 1..=k {
-        info!("Growing DAGs: iteration {i}.");
-        let mut cache = GrowthCache::new(inner_interactome.clone());
-        match grow(&interactome, &mut dag, &mut cache, &mut EdgeCost)? {
-            Some((weight, path)) => {
-                let path = path
-                    .into_iter()
-                    .filter_map(|node| node.left())
-                    .map(|node| interactome.inner_network.id_from_idx(node).cloned().unwrap())
-                    .collect::<Vec<_>>()
-                    .join("|");
-                println!("{i}\t{weight}\t{path}");
-            },
-            None => {
-                log::warn!("No more paths could be constructed. Stopping at iteration {i}.");
-                break;
-            }
+    let mut cache = GrowthCache::lazy();
+    cache.options = growth_options;
+
+    if let Some(output_dir) = output_dir {
+        fs::create_dir_all(output_dir)?;
+    }
+
+    let mut summary = GrowSummary::default();
+    // Only nonzero for costs which enumerate source-target paths, such as `--cost path`.
+    let mut paths_enumerated = 0;
+    // The hook can't fail, so the first error it runs into is returned once growth is done.
+    let mut failure = None;
+    let mut on_grow = |i, grown: &GrowResult, dag: &PartialDag<()>| {
+        if failure.is_some() {
+            return;
+        }
+
+        if grown.diagnostics.paths_enumerated > 0 {
+            info!("Enumerated {} source-target paths in iteration {i}.", grown.diagnostics.paths_enumerated);
+            paths_enumerated += grown.diagnostics.paths_enumerated;
         }
+        summary.iterations = i;
+        summary.total_cost += grown.weight;
+        failure = write_grown(out, &interactome, output_dir, i, grown, dag).err();
+    };
+    match &pool {
+        Some(pool) => {
+            grow_k_parallel(&interactome, &mut dag, &mut cache, &mut cost, pool, options.k, Some(&mut on_grow))?
+        }
+        None => grow_k(&interactome, &mut dag, &mut cache, &mut cost, options.k, Some(&mut on_grow))?,
+    };
+    if let Some(failure) = failure {
+        return Err(failure);
     }
 
-    Ok(())
+    if let Some(output_dir) = output_dir.filter(|_| options.dag_weights) {
+        let raw = parse_network(&interactome_path, options, Transform::Identity)?;
+        write_dag_weights(&output_dir.join("dag_weights.tsv"), &interactome, &raw, &dag)?;
+    }
+
+    if paths_enumerated > 0 {
+        eprintln!("Enumerated {paths_enumerated} source-target paths while scoring candidates.");
+    }
+
+    let targets_connected = cache.options.until.is_met(&interactome, &dag);
+    if summary.iterations < options.k && !targets_connected && !cache.max_edges_reached() {
+        eprintln!(
+            "Warning: requested {} growth steps, completed {}: no more source-to-target paths available.",
+            options.k, summary.iterations
+        );
+    }
+
+    Ok(summary)
 }
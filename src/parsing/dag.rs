@@ -1,14 +1,26 @@
-use crate::parsing::network::Network;
+use std::{collections::HashMap, hash::RandomState, iter};
+
+use crate::parsing::{
+    data::EmptyTupleDataFactory,
+    network::{Network, NetworkIndexError},
+};
+use bimap::BiHashMap;
+use either::Either;
 use never::Never;
-use petgraph::algo::is_cyclic_directed;
+use petgraph::{
+    algo::{all_simple_paths, is_cyclic_directed},
+    visit::Dfs,
+};
 use thiserror::Error;
 
-use super::interactome::{Interactome, InteractomeAttachError};
+use super::interactome::{Interactome, InteractomeAttachError, SuperNode};
 
 #[derive(Debug, Error)]
 pub enum DAGCreationError {
     #[error(transparent)]
     InteractomeAttachError(#[from] InteractomeAttachError),
+    #[error(transparent)]
+    NetworkIndexError(#[from] NetworkIndexError),
     #[error("The passed in DAG has cycles!")]
     IsCyclic,
 }
@@ -16,6 +28,10 @@ pub enum DAGCreationError {
 /// A partial DAG.
 /// Note that only a subgraph of the network is guaranteed to be a DAG,
 /// but this subgraph can be empty.
+///
+/// The DAG need not be connected, nor touch any source or target: growth treats every DAG node (the super-source
+/// and super-target included) alike, as both the start and end of candidate paths. So paths grown from a forest of
+/// components join them to each other, and to the sources and targets, as their costs allow.
 #[derive(Clone, Debug)]
 pub struct PartialDag<E>(pub Interactome<E>);
 
@@ -33,4 +49,347 @@ impl<E: Clone + Default> PartialDag<E> {
 
         Ok(PartialDag(interactome))
     }
+
+    /// Remaps this DAG's node ids to those of `interactome`'s id_map by gene name,
+    /// for a DAG which was not built over the interactome's id_map (see `Network::from_lines_using_id_map`).
+    ///
+    /// If any of the DAG's genes are not in the interactome, the DAG is left untouched,
+    /// and those genes are returned instead.
+    pub fn reconcile_ids_with<W>(&mut self, interactome: &Interactome<W>) -> Result<(), Vec<String>> {
+        let network = &mut self.0.inner_network;
+
+        let mut remapped = HashMap::new();
+        let mut missing = vec![];
+        for (name, &id) in network.id_map.iter() {
+            match interactome.inner_network.id_map.get_by_left(name) {
+                Some(&new_id) => {
+                    remapped.insert(id, new_id);
+                }
+                None => missing.push(name.clone()),
+            }
+        }
+
+        if !missing.is_empty() {
+            missing.sort();
+            return Err(missing);
+        }
+
+        let remap = |node: Either<usize, SuperNode>| node.map_left(|id| remapped[&id]);
+
+        let mut graph = network.graph.clone();
+        graph.clear();
+        for node in network.graph.nodes() {
+            graph.add_node(remap(node));
+        }
+        for (a, b, edge) in network.graph.all_edges() {
+            graph.add_edge(remap(a), remap(b), edge.clone());
+        }
+
+        network.graph = graph;
+        network.id_map = network
+            .id_map
+            .iter()
+            .map(|(name, id)| (name.clone(), remapped[id]))
+            .collect();
+        for id in self.0.sources.iter_mut().chain(self.0.targets.iter_mut()) {
+            *id = remapped[id];
+        }
+
+        Ok(())
+    }
+
+    /// Whether every one of `targets` (by id) is in the DAG, with a path to it from the super-source.
+    pub fn connects_targets(&self, targets: &[usize]) -> bool {
+        let graph = &self.0.inner_network.graph;
+        if !graph.contains_node(Either::Right(SuperNode::Source)) {
+            return targets.is_empty();
+        }
+
+        let mut dfs = Dfs::new(graph, Either::Right(SuperNode::Source));
+        while dfs.next(graph).is_some() {}
+
+        targets.iter().all(|&target| dfs.discovered.contains(&Either::Left(target)))
+    }
+
+    /// The number of edges between two genes, leaving out those to and from super-nodes.
+    pub fn interaction_count(&self) -> usize {
+        self.0.inner_network.graph.all_edges().filter(|(a, b, _)| a.is_left() && b.is_left()).count()
+    }
+
+    /// The betweenness of every (non-super) node: the fraction of the paths from the super-source to the
+    /// super-target which pass through it. Nodes are sorted from most to least central, and then by name.
+    ///
+    /// This enumerates every such path, of which there can be exponentially many, so it is only meant for
+    /// grown DAGs, which are small. Every node has a betweenness of 0 if there are no paths.
+    pub fn node_betweenness(&self) -> Vec<(String, f64)> {
+        let network = &self.0.inner_network;
+        let graph = &network.graph;
+
+        let mut counts = graph.nodes().filter_map(|node| node.left()).map(|id| (id, 0)).collect::<HashMap<_, usize>>();
+        let mut total = 0;
+        let (source, target) = (Either::Right(SuperNode::Source), Either::Right(SuperNode::Target));
+        if graph.contains_node(source) && graph.contains_node(target) {
+            for path in all_simple_paths::<Vec<_>, _, RandomState>(graph, source, target, 0, None) {
+                total += 1;
+                for id in path.into_iter().filter_map(|node| node.left()) {
+                    *counts.entry(id).or_default() += 1;
+                }
+            }
+        }
+
+        let mut betweenness = counts
+            .into_iter()
+            .map(|(id, count)| (network.display_name(id), if total == 0 { 0.0 } else { count as f64 / total as f64 }))
+            .collect::<Vec<_>>();
+        betweenness.sort_by(|(a, x), (b, y)| y.total_cmp(x).then_with(|| a.cmp(b)));
+        betweenness
+    }
+
+    /// Whether the DAG has an edge from the gene `a` to the gene `b`.
+    /// Errors if either gene is unknown to the DAG's id_map.
+    pub fn contains_edge_by_name(&self, a: &str, b: &str) -> Result<bool, NetworkIndexError> {
+        let network = &self.0.inner_network;
+        let (a, b) = (network.get_node(a)?, network.get_node(b)?);
+        Ok(network.graph.contains_edge(Either::Left(a), Either::Left(b)))
+    }
+}
+
+impl PartialDag<()> {
+    /// Builds a DAG directly from its `(a, b)` gene name edges, resolving genes through `id_map`
+    /// (usually the interactome's), instead of parsing a file. Errors on genes missing from `id_map`,
+    /// or, like `Self::new`, if the edges form a cycle.
+    pub fn from_edges(
+        edges: &[(String, String)],
+        id_map: &BiHashMap<String, usize>,
+        sources: &[String],
+        targets: &[String],
+    ) -> Result<Self, DAGCreationError> {
+        let mut network =
+            Network::from_lines_using_id_map::<EmptyTupleDataFactory, _>(iter::empty(), id_map, false)
+                .expect("parsing no lines can't fail");
+
+        for (a, b) in edges {
+            let mut resolve = |name: &String| {
+                let id = *id_map.get_by_left(name).ok_or_else(|| NetworkIndexError(name.clone()))?;
+                network.id_map.insert(name.clone(), id);
+                Ok::<_, NetworkIndexError>(Either::Left(id))
+            };
+            let (a, b) = (resolve(a)?, resolve(b)?);
+            network.graph.add_edge(a, b, ());
+        }
+
+        Self::new(network, sources, targets)
+    }
+}
+
+impl<E> PartialDag<E> {
+    /// The width of the DAG: the size of its largest antichain, i.e. the maximum number of
+    /// mutually incomparable (non-super) nodes.
+    ///
+    /// By Dilworth's theorem, this is equal to the minimum number of chains needed to cover the DAG,
+    /// which we compute exactly as `n - m`, where `m` is the size of a maximum matching in the bipartite
+    /// graph joining `u` to `v` whenever `u` reaches `v` (found with Kuhn's augmenting paths).
+    pub fn width(&self) -> usize {
+        let graph = &self.0.inner_network.graph;
+        let nodes = graph.nodes().filter(|node| node.is_left()).collect::<Vec<_>>();
+        let positions = nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| (*node, idx))
+            .collect::<HashMap<_, _>>();
+
+        // The transitive closure, restricted to non-super nodes.
+        let reachable = nodes
+            .iter()
+            .map(|&node| {
+                let mut dfs = Dfs::new(graph, node);
+                let mut descendents = vec![];
+                while let Some(next) = dfs.next(graph) {
+                    if next != node {
+                        if let Some(&idx) = positions.get(&next) {
+                            descendents.push(idx);
+                        }
+                    }
+                }
+                descendents
+            })
+            .collect::<Vec<_>>();
+
+        fn augment(
+            u: usize,
+            reachable: &[Vec<usize>],
+            seen: &mut [bool],
+            matched_to: &mut [Option<usize>],
+        ) -> bool {
+            for &v in &reachable[u] {
+                if seen[v] {
+                    continue;
+                }
+                seen[v] = true;
+
+                if matched_to[v].is_none_or(|w| augment(w, reachable, seen, matched_to)) {
+                    matched_to[v] = Some(u);
+                    return true;
+                }
+            }
+
+            false
+        }
+
+        let mut matched_to = vec![None; nodes.len()];
+        let mut matching = 0;
+        for u in 0..nodes.len() {
+            let mut seen = vec![false; nodes.len()];
+            if augment(u, &reachable, &mut seen, &mut matched_to) {
+                matching += 1;
+            }
+        }
+
+        nodes.len() - matching
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::{
+        alg::{
+            cost::EdgeCost,
+            grow::{grow, GrowthCache},
+        },
+        parsing::weight::WeightDataFactory,
+    };
+
+    use super::*;
+
+    #[test]
+    fn diamond_width() {
+        let network = Network::from_lines::<EmptyTupleDataFactory, _>(
+            vec![
+                Ok("A\tB".to_string()),
+                Ok("A\tC".to_string()),
+                Ok("B\tD".to_string()),
+                Ok("C\tD".to_string()),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+
+        let dag = PartialDag::<()>::new(network, &["A".to_string()], &["D".to_string()]).unwrap();
+
+        assert_eq!(dag.width(), 2);
+    }
+
+    #[test]
+    fn diamond_betweenness() {
+        let network = Network::from_lines::<EmptyTupleDataFactory, _>(
+            ["A\tB", "A\tC", "B\tD", "C\tD"].into_iter().map(|line| Ok(line.to_string())),
+        )
+        .unwrap();
+        let dag = PartialDag::<()>::new(network, &["A".to_string()], &["D".to_string()]).unwrap();
+
+        // Both paths pass through A and D, but only one through each of B and C.
+        assert_eq!(
+            dag.node_betweenness(),
+            vec![("A".to_string(), 1.0), ("D".to_string(), 1.0), ("B".to_string(), 0.5), ("C".to_string(), 0.5)]
+        );
+    }
+
+    #[test]
+    fn from_edges() {
+        let network = Network::<_, Never>::from_lines_infallible::<WeightDataFactory>(
+            ["A\tB\t1", "B\tC\t1", "A\tC\t1", "C\tD\t1"].into_iter().map(str::to_string),
+        )
+        .unwrap();
+        let edge = |a: &str, b: &str| (a.to_string(), b.to_string());
+        let (sources, targets) = (["A".to_string()], ["C".to_string()]);
+
+        let dag = PartialDag::from_edges(
+            &[edge("A", "B"), edge("B", "C"), edge("A", "C")],
+            &network.id_map,
+            &sources,
+            &targets,
+        )
+        .unwrap();
+        // The triangle, plus the super-source and super-target edges.
+        assert_eq!(dag.0.inner_network.edge_count(), 5);
+        assert!(dag.contains_edge_by_name("B", "C").unwrap());
+        assert_eq!(dag.0.inner_network.get_node("C").unwrap(), network.get_node("C").unwrap());
+
+        assert!(matches!(
+            PartialDag::from_edges(&[edge("A", "B"), edge("B", "D"), edge("D", "B")], &network.id_map, &sources, &targets),
+            Err(DAGCreationError::IsCyclic)
+        ));
+        assert!(matches!(
+            PartialDag::from_edges(&[edge("A", "Q")], &network.id_map, &sources, &targets),
+            Err(DAGCreationError::NetworkIndexError(NetworkIndexError(name))) if name == "Q"
+        ));
+    }
+
+    #[test]
+    fn reconcile_ids() {
+        let lines = |lines: &[&str]| lines.iter().map(|line| Ok(line.to_string())).collect::<Vec<_>>().into_iter();
+        let (sources, targets) = (["A".to_string()], ["C".to_string()]);
+
+        let interactome = Interactome::attach_sources_and_targets(
+            Network::from_lines::<WeightDataFactory, _>(lines(&["X\tC\t1", "B\tC\t1", "A\tB\t1"])).unwrap(),
+            &sources,
+            &targets,
+            true,
+        )
+        .unwrap();
+        // Built over its own id_map, so A, B, and C have different ids here.
+        let mut dag = PartialDag::<()>::new(
+            Network::from_lines::<EmptyTupleDataFactory, _>(lines(&["A\tB", "B\tC"])).unwrap(),
+            &sources,
+            &targets,
+        )
+        .unwrap();
+        assert_ne!(dag.0.inner_network.get_node("C").unwrap(), interactome.inner_network.get_node("C").unwrap());
+
+        dag.reconcile_ids_with(&interactome).unwrap();
+        for node in ["A", "B", "C"] {
+            assert_eq!(dag.0.inner_network.get_node(node).unwrap(), interactome.inner_network.get_node(node).unwrap());
+        }
+        let ab = interactome.inner_network.as_nodes(&["A", "B"]).unwrap();
+        assert!(dag.0.inner_network.graph.contains_edge(ab[0], ab[1]));
+        assert_eq!(dag.0.sources, interactome.sources);
+        assert_eq!(dag.0.targets, interactome.targets);
+
+        let mut stray = PartialDag::<()>::new(
+            Network::from_lines::<EmptyTupleDataFactory, _>(lines(&["A\tY", "Y\tZ"])).unwrap(),
+            &sources,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(stray.reconcile_ids_with(&interactome), Err(vec!["Y".to_string(), "Z".to_string()]));
+    }
+
+    #[test]
+    fn contains_edge_by_name() {
+        let folder = Path::new("./tests/fixtures/triangle");
+        let (sources, targets) = (["A".to_string()], ["C".to_string()]);
+
+        let network = Network::from_file::<WeightDataFactory>(&folder.join("interactome.txt")).unwrap();
+        let interactome = Interactome::attach_sources_and_targets(network, &sources, &targets, true).unwrap();
+        let mut dag = PartialDag::new(
+            Network::from_file_using_id_map::<EmptyTupleDataFactory>(
+                &folder.join("dag.txt"),
+                &interactome.inner_network.id_map,
+                false,
+            )
+            .unwrap(),
+            &sources,
+            &targets,
+        )
+        .unwrap();
+        let mut cache = GrowthCache::new(interactome.clone());
+        grow(&interactome, &mut dag, &mut cache, &mut EdgeCost).unwrap().unwrap();
+
+        assert!(dag.contains_edge_by_name("A", "C").unwrap());
+        assert!(dag.contains_edge_by_name("B", "C").unwrap());
+        assert!(!dag.contains_edge_by_name("C", "B").unwrap());
+        assert_eq!(dag.contains_edge_by_name("A", "Q").unwrap_err().0, "Q");
+    }
 }
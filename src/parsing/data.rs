@@ -8,24 +8,57 @@
 /// A data factory parses inputs
 /// from a list of strings on a specific line of the interactome input,
 /// with a guaranteed `Self::len()`. [non-deterministic lengths can lead to panics.]
+/// Factories may be configured at runtime, such as `ColumnWeightDataFactory` with its column.
+// `len` is the number of components a line's data spans, which is never empty, and `from_strs` is a parser.
+#[allow(clippy::len_without_is_empty, clippy::wrong_self_convention)]
 pub trait DataFactory<D> {
-    fn len() -> usize;
-    fn err_str() -> String;
-    fn from_strs(line: usize, strs: Vec<String>) -> Result<D, anyhow::Error>;
+    fn len(&self) -> usize;
+    fn err_str(&self) -> String;
+    fn from_strs(&self, line: usize, strs: Vec<String>) -> Result<D, anyhow::Error>;
+
+    /// Whether a line may have more components than `Self::len()`. These extra components
+    /// are not passed to `Self::from_strs`.
+    fn allows_extra_components(&self) -> bool {
+        false
+    }
 }
 
+#[derive(Clone, Copy, Debug, Default)]
 pub struct EmptyTupleDataFactory;
 
 impl DataFactory<()> for EmptyTupleDataFactory {
-    fn len() -> usize {
+    fn len(&self) -> usize {
         0
     }
 
-    fn err_str() -> String {
+    fn err_str(&self) -> String {
         "nothing following".to_string()
     }
 
-    fn from_strs(_line: usize, _strs: Vec<String>) -> Result<(), anyhow::Error> {
+    fn from_strs(&self, _line: usize, _strs: Vec<String>) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+}
+
+/// Accepts lines with any data following the source and target, without parsing (or copying) it.
+/// Useful for reading only the structure of a weighted interactome as a `Network<(), _>`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IgnoredDataFactory;
+
+impl DataFactory<()> for IgnoredDataFactory {
+    fn len(&self) -> usize {
+        0
+    }
+
+    fn err_str(&self) -> String {
+        "anything following".to_string()
+    }
+
+    fn from_strs(&self, _line: usize, _strs: Vec<String>) -> Result<(), anyhow::Error> {
         Ok(())
     }
+
+    fn allows_extra_components(&self) -> bool {
+        true
+    }
 }
@@ -0,0 +1,195 @@
+//! GraphML import and export, for moving networks (and grown DAGs) in and out of tools like Cytoscape.
+//!
+//! Nodes are written with their gene name as their GraphML `id`, and edges carry a `weight` data attribute.
+//! Super-nodes, along with their edges, are omitted: they are recreated by attaching sources and targets.
+
+use std::{fmt::Write, hash::Hash};
+
+use anyhow::anyhow;
+use bimap::BiHashMap;
+use quick_xml::{escape::escape, events::Event, Reader};
+
+use super::{
+    dag::PartialDag,
+    interactome::Interactome,
+    network::{Network, NetworkParsingError},
+    weight::{Weight, WeightDataFactory},
+};
+
+/// Writes a directed GraphML document over the named nodes of `id_map` which are in `nodes`,
+/// and the edges between them.
+fn write_graphml(
+    id_map: &BiHashMap<String, usize>,
+    nodes: impl Iterator<Item = usize>,
+    edges: impl Iterator<Item = (usize, usize, Option<f64>)>,
+) -> String {
+    let mut nodes = nodes.filter_map(|node| id_map.get_by_right(&node)).collect::<Vec<_>>();
+    nodes.sort();
+    let mut edges = edges
+        .filter_map(|(a, b, weight)| Some((id_map.get_by_right(&a)?, id_map.get_by_right(&b)?, weight)))
+        .collect::<Vec<_>>();
+    edges.sort_by(|(a, b, _), (c, d, _)| (a, b).cmp(&(c, d)));
+
+    let mut graphml = String::new();
+    graphml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    graphml.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    graphml.push_str("  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"double\"/>\n");
+    graphml.push_str("  <graph id=\"G\" edgedefault=\"directed\">\n");
+    for node in nodes {
+        writeln!(graphml, "    <node id=\"{}\"/>", escape(node.as_str())).unwrap();
+    }
+    for (a, b, weight) in edges {
+        let (a, b) = (escape(a.as_str()), escape(b.as_str()));
+        match weight {
+            Some(weight) => writeln!(
+                graphml,
+                "    <edge source=\"{a}\" target=\"{b}\">\n      <data key=\"weight\">{weight}</data>\n    </edge>"
+            ),
+            None => writeln!(graphml, "    <edge source=\"{a}\" target=\"{b}\"/>"),
+        }
+        .unwrap();
+    }
+    graphml.push_str("  </graph>\n</graphml>\n");
+
+    graphml
+}
+
+impl<S: Eq + Hash + Copy + Ord> Network<Weight, S> {
+    /// Exports this network as a directed GraphML document. See the module documentation for its layout.
+    pub fn to_graphml(&self) -> String {
+        write_graphml(
+            &self.id_map,
+            self.graph.nodes().filter_map(|node| node.left()),
+            self.graph
+                .all_edges()
+                .filter_map(|(a, b, weight)| Some((a.left()?, b.left()?, Some(weight.0)))),
+        )
+    }
+
+    /// Imports a GraphML document, such as one written by `Self::to_graphml`. Every edge needs a `weight`,
+    /// which is used as-is (that is, it is not transformed like an interactome's weights may be).
+    pub fn from_graphml(graphml: &str) -> Result<Self, NetworkParsingError> {
+        let mut network = Self::from_lines_infallible::<WeightDataFactory>(std::iter::empty())?;
+        let mut reader = Reader::from_str(graphml);
+        reader.config_mut().trim_text(true);
+
+        let attribute = |event: &quick_xml::events::BytesStart, name: &str| -> Result<String, NetworkParsingError> {
+            let value = event
+                .try_get_attribute(name)
+                .map_err(|err| anyhow!(err))?
+                .ok_or_else(|| anyhow!("A GraphML <{}> has no {name}", String::from_utf8_lossy(event.name().0)))?;
+            Ok(value.unescape_value().map_err(|err| anyhow!(err))?.to_string())
+        };
+
+        // The edge being read, and whether it is inside its weight's <data>.
+        let mut edge: Option<(String, String, Option<f64>)> = None;
+        let mut in_weight = false;
+        loop {
+            match reader.read_event().map_err(|err| anyhow!(err))? {
+                Event::Start(event) | Event::Empty(event) if event.local_name().as_ref() == b"node" => {
+                    let name = attribute(&event, "id")?;
+                    if network.get_node(&name).is_err() {
+                        network.add_node_with_name(&name).unwrap();
+                    }
+                }
+                Event::Start(event) if event.local_name().as_ref() == b"edge" => {
+                    edge = Some((attribute(&event, "source")?, attribute(&event, "target")?, None));
+                }
+                Event::Empty(event) if event.local_name().as_ref() == b"edge" => {
+                    let (a, b) = (attribute(&event, "source")?, attribute(&event, "target")?);
+                    return Err(anyhow!("The GraphML edge {a} -> {b} has no weight").into());
+                }
+                Event::Start(event) if event.local_name().as_ref() == b"data" => {
+                    in_weight = edge.is_some() && attribute(&event, "key")? == "weight";
+                }
+                Event::Text(text) if in_weight => {
+                    let weight = text.decode().map_err(|err| anyhow!(err))?;
+                    let weight = weight.parse::<f64>().map_err(|err| anyhow!("Invalid GraphML weight {weight}: {err}"))?;
+                    if let Some((_, _, edge_weight)) = &mut edge {
+                        *edge_weight = Some(weight);
+                    }
+                }
+                Event::End(event) if event.local_name().as_ref() == b"data" => in_weight = false,
+                Event::End(event) if event.local_name().as_ref() == b"edge" => {
+                    let (a, b, weight) = edge.take().unwrap();
+                    let weight = weight.ok_or_else(|| anyhow!("The GraphML edge {a} -> {b} has no weight"))?;
+                    network.add_edge_by_name_allocating(&a, &b, Weight(weight));
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        Ok(network)
+    }
+}
+
+impl Interactome<Weight> {
+    /// Exports the interactome, without its super-nodes, as GraphML. See `Network::to_graphml`.
+    pub fn to_graphml(&self) -> String {
+        self.inner_network.to_graphml()
+    }
+}
+
+impl PartialDag<()> {
+    /// Exports the DAG, without its super-nodes, as GraphML, weighting its edges by their weight in `interactome`.
+    /// Edges which are not in `interactome` are written without a weight.
+    pub fn to_graphml(&self, interactome: &Interactome<Weight>) -> String {
+        let graph = &self.0.inner_network.graph;
+        write_graphml(
+            &self.0.inner_network.id_map,
+            graph.nodes().filter_map(|node| node.left()),
+            graph.all_edges().filter_map(|(a, b, _)| {
+                let weight = interactome.inner_network.graph.edge_weight(a, b).map(|weight| weight.0);
+                Some((a.left()?, b.left()?, weight))
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use never::Never;
+
+    use crate::parsing::{data::EmptyTupleDataFactory, interactome::SuperNode};
+
+    use super::*;
+
+    #[test]
+    fn graphml_round_trip() {
+        let network = Network::<_, Never>::from_lines_infallible::<WeightDataFactory>(
+            ["A\tB\t0.5", "B\tC\t0.25", "A & B\t<C>\t1e-3"].into_iter().map(str::to_string),
+        )
+        .unwrap();
+        let interactome =
+            Interactome::attach_sources_and_targets(network, &["A".to_string()], &["C".to_string()], true).unwrap();
+
+        let graphml = interactome.to_graphml();
+        assert!(graphml.contains("<node id=\"A &amp; B\"/>"));
+        assert!(!graphml.contains("Super"));
+
+        let imported = Network::<Weight, SuperNode>::from_graphml(&graphml).unwrap();
+        assert_eq!(imported.to_edge_list(), interactome.inner_network.to_edge_list());
+        for (a, b, weight) in [("A", "B", 0.5), ("B", "C", 0.25), ("A & B", "<C>", 1e-3)] {
+            assert_eq!(imported.edge_weight_by_name(a, b).unwrap().unwrap().0, weight);
+        }
+        assert_eq!(imported.to_graphml(), graphml);
+
+        let dag = PartialDag::new(
+            Network::from_lines_using_id_map::<EmptyTupleDataFactory, _>(
+                iter::once(Ok("A\tB".to_string())),
+                &interactome.inner_network.id_map,
+                false,
+            )
+            .unwrap(),
+            &["A".to_string()],
+            &["C".to_string()],
+        )
+        .unwrap();
+        let imported = Network::<Weight, Never>::from_graphml(&dag.to_graphml(&interactome)).unwrap();
+        assert_eq!(imported.to_edge_list(), "A\tB\n");
+        assert_eq!(imported.edge_weight_by_name("A", "B").unwrap().unwrap().0, 0.5);
+    }
+}
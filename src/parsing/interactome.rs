@@ -1,11 +1,25 @@
-use std::cmp::Ordering;
+use std::{
+    cmp::Ordering,
+    collections::HashSet,
+    fmt::{self, Write},
+};
 
 use either::Either;
 use never::Never;
-use petgraph::Direction;
+use petgraph::{
+    visit::{Dfs, Reversed},
+    Direction,
+};
 use thiserror::Error;
 
-use crate::parsing::network::Network;
+use crate::{
+    parsing::{
+        dag::PartialDag,
+        network::{Network, UnnamedNodeError},
+        weight::Weight,
+    },
+    util::Terminal,
+};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum SuperNode {
@@ -32,6 +46,18 @@ impl PartialOrd for SuperNode {
     }
 }
 
+impl SuperNode {
+    /// The name super-nodes are printed with (see `Interactome::name_from_idx`), which no gene may have.
+    pub const fn name(self) -> &'static str {
+        match self {
+            SuperNode::Source => "[[Super Source]]",
+            SuperNode::Target => "[[Super Target]]",
+        }
+    }
+}
+
+type NodeSet = HashSet<Either<usize, SuperNode>>;
+
 #[derive(Clone, Debug)]
 pub struct Interactome<E> {
     pub inner_network: Network<E, SuperNode>,
@@ -46,6 +72,36 @@ pub enum InteractomeAttachError {
     SourceNotExists(String),
     #[error("Target '{0}' does not exist in the interactome.")]
     TargetNotExists(String),
+    #[error("The interactome has no nodes, so no sources or targets could be attached to it.")]
+    EmptyNetwork,
+    #[error("The interactome has a gene named '{0}', which is reserved for a super-node.")]
+    ReservedName(String),
+}
+
+/// How many of the requested sources and targets were found in the interactome.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Coverage {
+    pub sources_found: usize,
+    pub sources_total: usize,
+    pub targets_found: usize,
+    pub targets_total: usize,
+    /// The sources and targets which were not in the interactome, in the order they were given.
+    pub dropped: Vec<String>,
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} sources found, {}/{} targets found.",
+            self.sources_found, self.sources_total, self.targets_found, self.targets_total
+        )?;
+        if !self.dropped.is_empty() {
+            write!(f, " Missing: {}", self.dropped.join(", "))?;
+        }
+
+        Ok(())
+    }
 }
 
 impl<E: Default + Clone> Interactome<E> {
@@ -55,16 +111,35 @@ impl<E: Default + Clone> Interactome<E> {
         targets: &[String],
         require_sources_and_targets: bool
     ) -> Result<Self, InteractomeAttachError> {
+        if require_sources_and_targets && network.node_count() == 0 {
+            return Err(InteractomeAttachError::EmptyNetwork);
+        }
+
+        // Otherwise, the gene would be printed the same as a super-node.
+        for name in [SuperNode::Source.name(), SuperNode::Target.name()] {
+            if network.id_map.contains_left(name) {
+                return Err(InteractomeAttachError::ReservedName(name.to_string()));
+            }
+        }
+
         let mut network = network.cast_over_never();
         let super_source = network.graph.add_node(Either::Right(SuperNode::Source));
         let super_target = network.graph.add_node(Either::Right(SuperNode::Target));
 
-        network
+        let pruned_sources = network
             .prune(sources, Direction::Incoming, require_sources_and_targets)
             .map_err(|err| InteractomeAttachError::SourceNotExists(err.0))?;
-        network
+        let pruned_targets = network
             .prune(targets, Direction::Outgoing, require_sources_and_targets)
             .map_err(|err| InteractomeAttachError::TargetNotExists(err.0))?;
+        log::info!(
+            "Pruned {} incoming edges from sources and {} outgoing edges from targets.",
+            pruned_sources.len(),
+            pruned_targets.len()
+        );
+        for (a, b) in pruned_sources.iter().chain(&pruned_targets) {
+            log::debug!("Pruned edge {a} -> {b}");
+        }
 
         let sources = sources
             .iter()
@@ -94,7 +169,7 @@ impl<E: Default + Clone> Interactome<E> {
                     return None;
                 }
                 
-                Some(target_id.ok_or_else(|| InteractomeAttachError::SourceNotExists(target.to_string())))
+                Some(target_id.ok_or_else(|| InteractomeAttachError::TargetNotExists(target.to_string())))
             })
             .collect::<Result<Vec<_>, _>>()?;
 
@@ -117,12 +192,268 @@ impl<E: Default + Clone> Interactome<E> {
         })
     }
 
+    /// Like `Self::attach_sources_and_targets`, but drops any sources and targets which are not in `network`
+    /// (rather than failing), reporting what was dropped. Only fails if `network` is empty.
+    pub fn attach_with_coverage(
+        network: Network<E, Never>,
+        sources: &[String],
+        targets: &[String],
+    ) -> Result<(Self, Coverage), InteractomeAttachError> {
+        if network.node_count() == 0 {
+            return Err(InteractomeAttachError::EmptyNetwork);
+        }
+
+        let dropped = sources
+            .iter()
+            .chain(targets)
+            .filter(|node| !network.id_map.contains_left(*node))
+            .cloned()
+            .collect();
+        let interactome = Self::attach_sources_and_targets(network, sources, targets, false)?;
+        let coverage = Coverage {
+            sources_found: interactome.sources.len(),
+            sources_total: sources.len(),
+            targets_found: interactome.targets.len(),
+            targets_total: targets.len(),
+            dropped,
+        };
+
+        Ok((interactome, coverage))
+    }
+
+    /// The number of edges in this interactome which do not touch the super-source or super-target.
+    pub fn non_super_edge_count(&self) -> usize {
+        let graph = &self.inner_network.graph;
+        graph.edge_count()
+            - graph.neighbors_directed(Either::Right(SuperNode::Source), Direction::Outgoing).count()
+            - graph.neighbors_directed(Either::Right(SuperNode::Target), Direction::Incoming).count()
+    }
+
+    /// Resolves the genes along `path` to their names, dropping any super-nodes. This is how grown paths are printed.
+    /// Errors on the first gene without a name, rather than making one up.
+    pub fn strip_super_and_name(&self, path: &[Either<usize, SuperNode>]) -> Result<Vec<String>, UnnamedNodeError> {
+        path.iter()
+            .filter_map(|node| node.left())
+            .map(|node| self.inner_network.id_from_idx(node).cloned().ok_or(UnnamedNodeError(node)))
+            .collect()
+    }
+
     /// Gets a pretty-printed name of the string from a node index.
     pub fn name_from_idx(&self, id: Either<usize, SuperNode>) -> Option<String> {
         match id {
             Either::Left(id) => self.inner_network.id_from_idx(id).cloned(),
-            Either::Right(SuperNode::Source) => Some("[[Super Source]]".to_string()),
-            Either::Right(SuperNode::Target) => Some("[[Super Target]]".to_string())
+            Either::Right(node) => Some(node.name().to_string()),
+        }
+    }
+
+    /// Like `Self::name_from_idx`, but for output: unnamed genes are written as in `Network::display_name`.
+    pub fn display_name(&self, id: Either<usize, SuperNode>) -> String {
+        match id {
+            Either::Left(id) => self.inner_network.display_name(id),
+            Either::Right(node) => node.name().to_string(),
+        }
+    }
+
+    /// Removes the super-source and super-target (along with their edges),
+    /// returning the underlying network. This is roughly the inverse of
+    /// `Self::attach_sources_and_targets`, though pruned edges are not restored.
+    pub fn into_plain_network(self) -> Network<E, Never> {
+        let mut network = self.inner_network;
+        network.graph.remove_node(Either::Right(SuperNode::Source));
+        network.graph.remove_node(Either::Right(SuperNode::Target));
+
+        network.destroy_right_nodes()
+    }
+
+    /// The nodes reachable from the super-source, and the nodes which can reach the super-target.
+    fn reachability(&self) -> (NodeSet, NodeSet) {
+        let graph = &self.inner_network.graph;
+
+        let from_source = {
+            let mut reached = HashSet::new();
+            let mut dfs = Dfs::new(graph, Either::Right(SuperNode::Source));
+            while let Some(node) = dfs.next(graph) {
+                reached.insert(node);
+            }
+            reached
+        };
+
+        let to_target = {
+            let mut reached = HashSet::new();
+            let mut dfs = Dfs::new(Reversed(graph), Either::Right(SuperNode::Target));
+            while let Some(node) = dfs.next(Reversed(graph)) {
+                reached.insert(node);
+            }
+            reached
+        };
+
+        (from_source, to_target)
+    }
+
+    /// The edges between genes which lie on some path from the super-source to the super-target:
+    /// that is, edges out of a node reachable from the super-source, into a node which can reach the super-target.
+    /// No other edge can ever be grown into a DAG.
+    pub fn usable_edges(&self) -> HashSet<(usize, usize)> {
+        let (from_source, to_target) = self.reachability();
+
+        self.inner_network
+            .graph
+            .all_edges()
+            .filter(|(a, b, _)| from_source.contains(a) && to_target.contains(b))
+            .filter_map(|(a, b, _)| Some((a.left()?, b.left()?)))
+            .collect()
+    }
+
+    /// Removes every edge between genes which is not in `Self::usable_edges`, returning how many were removed.
+    /// Nodes (and super-node edges) are kept, even if they are left without any usable edge.
+    pub fn prune_to_usable(&mut self) -> usize {
+        let usable = self.usable_edges();
+        let unusable = self
+            .inner_network
+            .graph
+            .all_edges()
+            .filter_map(|(a, b, _)| Some((a.left()?, b.left()?)))
+            .filter(|edge| !usable.contains(edge))
+            .collect::<Vec<_>>();
+
+        for &(a, b) in &unusable {
+            self.inner_network.graph.remove_edge(Either::Left(a), Either::Left(b));
+        }
+
+        unusable.len()
+    }
+
+    /// For each non-super node, whether it is reachable from the super-source, and whether it can reach
+    /// the super-target. Only nodes which are `(true, true)` can ever be grown into a DAG.
+    pub fn reachability_table(&self) -> Vec<(String, bool, bool)> {
+        let graph = &self.inner_network.graph;
+        let (from_source, to_target) = self.reachability();
+
+        graph
+            .nodes()
+            .filter(|node| node.is_left())
+            .map(|node| {
+                (
+                    self.display_name(node),
+                    from_source.contains(&node),
+                    to_target.contains(&node),
+                )
+            })
+            .collect()
+    }
+
+    /// Renders this interactome as a Graphviz DOT digraph, annotated by `dag` (which must share its id_map):
+    /// sources are green, targets red, and other DAG nodes blue. DAG edges are bold, while the remaining
+    /// (candidate) edges are faint. The super-source and super-target are drawn as triangles.
+    pub fn to_dot_annotated(&self, dag: &PartialDag<()>) -> String {
+        let graph = &self.inner_network.graph;
+        let dag_graph = &dag.0.inner_network.graph;
+        let id = |node: Either<usize, SuperNode>| match node {
+            Either::Left(id) => format!("n{id}"),
+            Either::Right(SuperNode::Source) => "super_source".to_string(),
+            Either::Right(SuperNode::Target) => "super_target".to_string(),
+        };
+
+        let mut dot = "digraph {\n".to_string();
+
+        let mut nodes = graph.nodes().collect::<Vec<_>>();
+        nodes.sort();
+        for node in nodes {
+            let attributes = match node {
+                Either::Right(SuperNode::Source) => "label=\"source\", shape=invtriangle".to_string(),
+                Either::Right(SuperNode::Target) => "label=\"target\", shape=triangle".to_string(),
+                Either::Left(idx) => {
+                    let name = self.inner_network.id_from_idx(idx).unwrap();
+                    let color = if self.sources.contains(&idx) {
+                        Some("green")
+                    } else if self.targets.contains(&idx) {
+                        Some("red")
+                    } else if dag_graph.contains_node(node) {
+                        Some("blue")
+                    } else {
+                        None
+                    };
+
+                    let label = format!("label=\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""));
+                    match color {
+                        Some(color) => format!("{label}, color={color}"),
+                        None => label,
+                    }
+                }
+            };
+            writeln!(dot, "    {} [{attributes}];", id(node)).unwrap();
+        }
+
+        let mut edges = graph.all_edges().map(|(a, b, _)| (a, b)).collect::<Vec<_>>();
+        edges.sort();
+        for (a, b) in edges {
+            let style = if dag_graph.contains_edge(a, b) { "style=bold" } else { "color=gray80" };
+            writeln!(dot, "    {} -> {} [{style}];", id(a), id(b)).unwrap();
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl Interactome<Weight> {
+    /// Like `Self::attach_sources_and_targets`, but weights the super-source's and super-target's edges
+    /// by each terminal's weight (see `Self::weight_terminals`).
+    pub fn attach_weighted_sources_and_targets(
+        network: Network<Weight, Never>,
+        sources: &[Terminal],
+        targets: &[Terminal],
+        require_sources_and_targets: bool,
+    ) -> Result<Self, InteractomeAttachError> {
+        let names = |terminals: &[Terminal]| terminals.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>();
+        let mut interactome = Self::attach_sources_and_targets(
+            network,
+            &names(sources),
+            &names(targets),
+            require_sources_and_targets,
+        )?;
+        interactome.weight_terminals(sources, targets);
+
+        Ok(interactome)
+    }
+
+    /// Sets the weight of every edge out of the super-source and into the super-target.
+    ///
+    /// These edges otherwise have the default weight of 0, which makes them free next to transformed
+    /// interactome weights (e.g. the log transform maps even a probability of 1 to a positive weight),
+    /// so paths entering through another source are favored. Passing the transformed weight of a certain
+    /// interaction puts them on the same scale. Call this before `Self::weight_terminals`, which overrides it.
+    pub fn weight_super_edges(&mut self, weight: Weight) {
+        let super_nodes = [Either::Right(SuperNode::Source), Either::Right(SuperNode::Target)];
+        for (a, b, edge) in self.inner_network.graph.all_edges_mut() {
+            if super_nodes.contains(&a) || super_nodes.contains(&b) {
+                *edge = weight;
+            }
+        }
+    }
+
+    /// Sets the weight of the edge from the super-source to each weighted source, and of the edge
+    /// from each weighted target to the super-target. Unweighted and missing terminals are left untouched.
+    pub fn weight_terminals(&mut self, sources: &[Terminal], targets: &[Terminal]) {
+        let super_source = Either::Right(SuperNode::Source);
+        let super_target = Either::Right(SuperNode::Target);
+
+        for (name, weight) in sources {
+            if let (Ok(id), Some(weight)) = (self.inner_network.get_node(name), weight) {
+                let source = Either::Left(id);
+                if let Some(edge) = self.inner_network.graph.edge_weight_mut(super_source, source) {
+                    *edge = Weight(*weight);
+                }
+            }
+        }
+
+        for (name, weight) in targets {
+            if let (Ok(id), Some(weight)) = (self.inner_network.get_node(name), weight) {
+                let target = Either::Left(id);
+                if let Some(edge) = self.inner_network.graph.edge_weight_mut(target, super_target) {
+                    *edge = Weight(*weight);
+                }
+            }
         }
     }
 }
@@ -161,13 +492,317 @@ mod tests {
         .unwrap();
 
         assert_eq!(
-            interactome
-                .inner_network
-                .graph
-                .edge_references()
-                .collect::<Vec<_>>()
-                .len(),
+            interactome.inner_network.edge_count(),
             7 + 3 + 2
         );
+        assert_eq!(interactome.non_super_edge_count(), 7);
+        // 9 genes, along with the super-source and super-target
+        assert_eq!(interactome.inner_network.node_count(), 9 + 2);
+    }
+
+    #[test]
+    fn strip_super_and_name() {
+        let network = Network::from_lines::<WeightDataFactory, _>(
+            ["A\tB\t0.5", "B\tC\t0.5"].into_iter().map(|line| Ok(line.to_string())),
+        )
+        .unwrap();
+        let interactome =
+            Interactome::attach_sources_and_targets(network, &["A".to_string()], &["C".to_string()], true).unwrap();
+
+        let mut path = vec![Either::Right(SuperNode::Source)];
+        path.extend(interactome.inner_network.as_nodes(&["A", "B", "C"]).unwrap());
+        path.push(Either::Right(SuperNode::Target));
+
+        assert_eq!(interactome.strip_super_and_name(&path).unwrap(), vec!["A", "B", "C"]);
+        assert!(interactome.strip_super_and_name(&path[..1]).unwrap().is_empty());
+
+        // An unnamed node's id isn't passed off as a gene name.
+        let mut interactome = interactome;
+        let unnamed = interactome.inner_network.add_node();
+        path.insert(2, Either::Left(unnamed));
+        assert!(matches!(interactome.strip_super_and_name(&path), Err(UnnamedNodeError(id)) if id == unnamed));
+        assert_eq!(interactome.display_name(Either::Right(SuperNode::Target)), "[[Super Target]]");
+    }
+
+    #[test]
+    fn reserved_names() {
+        let network = Network::from_lines::<WeightDataFactory, _>(
+            ["[[Super Source]]\tA\t0.5", "A\tB\t0.5"].into_iter().map(|line| Ok(line.to_string())),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            Interactome::attach_sources_and_targets(network, &["A".to_string()], &["B".to_string()], false),
+            Err(InteractomeAttachError::ReservedName(name)) if name == "[[Super Source]]"
+        ));
+    }
+
+    #[test]
+    fn prune_to_usable() {
+        let network = Network::from_lines::<WeightDataFactory, _>(
+            ["A\t1", "B\t1", "C\t2", "K\tC", "1\t3", "2\t3", "3\tX", "3\tY", "Q\t1", "3\tZ"]
+                .into_iter()
+                .map(|line| Ok(format!("{line}\t0.123"))),
+        )
+        .unwrap();
+        let mut interactome = Interactome::attach_sources_and_targets(
+            network,
+            &["A".to_string(), "B".to_string(), "C".to_string()],
+            &["X".to_string(), "Y".to_string()],
+            true,
+        )
+        .unwrap();
+
+        let id = |name: &str| interactome.inner_network.get_node(name).unwrap();
+        let usable = interactome.usable_edges();
+        assert_eq!(usable.len(), 7);
+        // K -> C leads into a source, Q -> 1 can't be reached from a source, and 3 -> Z leads nowhere.
+        for (a, b) in [("K", "C"), ("Q", "1"), ("3", "Z")] {
+            assert!(!usable.contains(&(id(a), id(b))));
+        }
+
+        assert_eq!(interactome.prune_to_usable(), 2);
+        assert_eq!(interactome.non_super_edge_count(), 7);
+        assert_eq!(interactome.usable_edges(), usable);
+    }
+
+    #[test]
+    fn prune_reports_removed_edges() {
+        let mut network: Network<_, Never> = Network::from_lines::<WeightDataFactory, _>(
+            ["A\t1\t0.5", "K\tC\t0.5", "L\tC\t0.5", "3\tX\t0.5", "X\t4\t0.5"]
+                .into_iter()
+                .map(|line| Ok(line.to_string())),
+        )
+        .unwrap();
+
+        let mut removed = network.prune(&["A".to_string(), "C".to_string()], Direction::Incoming, true).unwrap();
+        removed.sort();
+        assert_eq!(removed, vec![("K".to_string(), "C".to_string()), ("L".to_string(), "C".to_string())]);
+
+        let removed = network.prune(&["X".to_string()], Direction::Outgoing, true).unwrap();
+        assert_eq!(removed, vec![("X".to_string(), "4".to_string())]);
+        assert_eq!(network.edge_count(), 2);
+
+        assert!(network.prune(&["Q".to_string()], Direction::Incoming, false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn weighted_terminals() {
+        let network = Network::from_lines::<WeightDataFactory, _>(
+            ["A\tX\t0.5", "B\tX\t0.5"].into_iter().map(|line| Ok(line.to_string())),
+        )
+        .unwrap();
+
+        let interactome = Interactome::attach_weighted_sources_and_targets(
+            network,
+            &[("A".to_string(), Some(0.25)), ("B".to_string(), None)],
+            &[("X".to_string(), Some(2.0))],
+            true,
+        )
+        .unwrap();
+
+        let graph = &interactome.inner_network.graph;
+        let [a, b, x] = interactome.inner_network.as_nodes(&["A", "B", "X"]).unwrap()[..] else {
+            unreachable!()
+        };
+        assert_eq!(graph.edge_weight(Either::Right(SuperNode::Source), a).unwrap().0, 0.25);
+        assert_eq!(graph.edge_weight(Either::Right(SuperNode::Source), b).unwrap().0, 0.0);
+        assert_eq!(graph.edge_weight(x, Either::Right(SuperNode::Target)).unwrap().0, 2.0);
+    }
+
+    #[test]
+    fn weighted_super_edges_change_paths() {
+        use crate::{
+            alg::{
+                cost::EdgeCost,
+                grow::{produce_dag, GrowthCache},
+            },
+            parsing::{dag::PartialDag, data::EmptyTupleDataFactory},
+        };
+
+        let lines = |lines: &[&str]| lines.iter().map(|line| Ok(line.to_string())).collect::<Vec<_>>().into_iter();
+        let (sources, targets) = (["A".to_string(), "B".to_string()], ["D".to_string()]);
+
+        // Against the DAG A -> D, entering through the source B is cheapest only while super edges are free.
+        let network =
+            Network::from_lines::<WeightDataFactory, _>(lines(&["A\tD\t1", "B\tD\t0.3", "A\tC\t0.2", "C\tD\t0.2"]))
+                .unwrap();
+        let mut interactome = Interactome::attach_sources_and_targets(network, &sources, &targets, true).unwrap();
+        let dag = PartialDag::new(
+            Network::from_lines_using_id_map::<EmptyTupleDataFactory, _>(
+                lines(&["A\tD"]),
+                &interactome.inner_network.id_map,
+                false,
+            )
+            .unwrap(),
+            &sources,
+            &targets,
+        )
+        .unwrap();
+
+        let first_hop = |interactome: &Interactome<Weight>| {
+            let mut cache = GrowthCache::new(interactome.clone());
+            let (_, path) = produce_dag(interactome, &dag, &mut cache, &mut EdgeCost).unwrap().unwrap();
+            interactome.inner_network.id_from_idx(path[1].left().unwrap()).cloned().unwrap()
+        };
+
+        assert_eq!(first_hop(&interactome), "B");
+
+        interactome.weight_super_edges(Weight(0.2));
+        let [a, d] = interactome.inner_network.as_nodes(&["A", "D"]).unwrap()[..] else {
+            unreachable!()
+        };
+        assert_eq!(interactome.inner_network.graph.edge_weight(Either::Right(SuperNode::Source), a).unwrap().0, 0.2);
+        assert_eq!(interactome.inner_network.graph.edge_weight(d, Either::Right(SuperNode::Target)).unwrap().0, 0.2);
+        assert_eq!(interactome.inner_network.graph.edge_weight(a, d).unwrap().0, 1.0);
+        assert_eq!(first_hop(&interactome), "C");
+    }
+
+    #[test]
+    fn to_dot_annotated() {
+        use crate::parsing::data::EmptyTupleDataFactory;
+
+        let lines = |lines: &[&str]| lines.iter().map(|line| Ok(line.to_string())).collect::<Vec<_>>().into_iter();
+        let (sources, targets) = (["A".to_string()], ["C".to_string()]);
+
+        let network =
+            Network::from_lines::<WeightDataFactory, _>(lines(&["A\tB\t1", "B\tC\t1", "A\tC\t1", "B\tD\t1"])).unwrap();
+        let interactome = Interactome::attach_sources_and_targets(network, &sources, &targets, true).unwrap();
+        let dag = PartialDag::new(
+            Network::from_lines_using_id_map::<EmptyTupleDataFactory, _>(
+                lines(&["A\tB", "B\tC"]),
+                &interactome.inner_network.id_map,
+                false,
+            )
+            .unwrap(),
+            &sources,
+            &targets,
+        )
+        .unwrap();
+
+        let dot = interactome.to_dot_annotated(&dag);
+        let id = |name| format!("n{}", interactome.inner_network.get_node(name).unwrap());
+
+        assert!(dot.starts_with("digraph {\n"));
+        assert!(dot.contains(&format!("{} [label=\"A\", color=green];", id("A"))));
+        assert!(dot.contains(&format!("{} [label=\"B\", color=blue];", id("B"))));
+        assert!(dot.contains(&format!("{} [label=\"C\", color=red];", id("C"))));
+        assert!(dot.contains(&format!("{} [label=\"D\"];", id("D"))));
+        assert!(dot.contains("super_source [label=\"source\", shape=invtriangle];"));
+        assert!(dot.contains(&format!("super_source -> {} [style=bold];", id("A"))));
+        assert!(dot.contains(&format!("{} -> {} [style=bold];", id("B"), id("C"))));
+        assert!(dot.contains(&format!("{} -> {} [color=gray80];", id("A"), id("C"))));
+    }
+
+    #[test]
+    fn attach_with_coverage() {
+        let network = Network::from_lines::<WeightDataFactory, _>(
+            ["A\tX\t0.5", "B\tX\t0.5"].into_iter().map(|line| Ok(line.to_string())),
+        )
+        .unwrap();
+
+        let (interactome, coverage) = Interactome::attach_with_coverage(
+            network,
+            &["A".to_string(), "Z".to_string(), "B".to_string()],
+            &["X".to_string()],
+        )
+        .unwrap();
+
+        assert_eq!(interactome.sources.len(), 2);
+        assert_eq!(
+            coverage,
+            Coverage {
+                sources_found: 2,
+                sources_total: 3,
+                targets_found: 1,
+                targets_total: 1,
+                dropped: vec!["Z".to_string()],
+            }
+        );
+        assert_eq!(coverage.to_string(), "2/3 sources found, 1/1 targets found. Missing: Z");
+
+        let empty = Network::<Weight, Never>::from_lines::<WeightDataFactory, _>(std::iter::empty()).unwrap();
+        assert!(matches!(
+            Interactome::attach_with_coverage(empty, &[], &[]),
+            Err(InteractomeAttachError::EmptyNetwork)
+        ));
+    }
+
+    #[test]
+    fn attach_to_empty() {
+        for lines in [vec![], vec!["# source\ttarget\tweight", "", "# nothing here"]] {
+            let lines = lines.into_iter().map(|line| Ok(line.to_string()));
+            let empty = Network::<Weight, Never>::from_lines::<WeightDataFactory, _>(lines).unwrap();
+            assert_eq!(empty.node_count(), 0);
+
+            assert!(matches!(
+                Interactome::attach_sources_and_targets(empty.clone(), &["A".to_string()], &["B".to_string()], true),
+                Err(InteractomeAttachError::EmptyNetwork)
+            ));
+            // An empty DAG is still fine to grow from.
+            let interactome = Interactome::attach_sources_and_targets(empty, &["A".to_string()], &[], false).unwrap();
+            assert_eq!(interactome.inner_network.node_count(), 2);
+        }
+    }
+
+    #[test]
+    fn reachability_table() {
+        let network = Network::from_lines::<WeightDataFactory, _>(
+            vec![
+                Ok("A\t1\t0.123".to_string()),
+                Ok("B\t1\t0.123".to_string()),
+                Ok("C\t2\t0.123".to_string()),
+                Ok("K\tC\t0.123".to_string()),
+                Ok("1\t3\t0.123".to_string()),
+                Ok("2\t3\t0.123".to_string()),
+                Ok("3\tX\t0.123".to_string()),
+                Ok("3\tY\t0.123".to_string()),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+
+        let interactome = Interactome::attach_sources_and_targets(
+            network,
+            &["A".to_string(), "B".to_string(), "C".to_string()],
+            &["X".to_string(), "Y".to_string()],
+            true
+        )
+        .unwrap();
+
+        let table = interactome.reachability_table();
+        assert_eq!(table.len(), 9);
+        assert!(table.contains(&("K".to_string(), false, false)));
+        assert!(table.contains(&("3".to_string(), true, true)));
+    }
+
+    #[test]
+    fn into_plain_network_strips_super_nodes() {
+        let network = Network::from_lines::<WeightDataFactory, _>(
+            vec![
+                Ok("A\t1\t0.123".to_string()),
+                Ok("1\t2\t0.123".to_string()),
+                Ok("2\tX\t0.123".to_string()),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+
+        let interactome = Interactome::attach_sources_and_targets(
+            network,
+            &["A".to_string()],
+            &["X".to_string()],
+            true
+        )
+        .unwrap();
+
+        let plain = interactome.into_plain_network();
+
+        assert_eq!(plain.graph.node_count(), 4);
+        assert_eq!(plain.edge_count(), 3);
+        assert!(plain
+            .graph
+            .edge_references()
+            .all(|(a, b, _)| a.is_left() && b.is_left()));
     }
 }
@@ -1,5 +1,6 @@
 pub mod dag;
 pub mod data;
+pub mod graphml;
 pub mod interactome;
 pub mod network;
 pub mod weight;
@@ -2,21 +2,28 @@
 //! Algorithms here aren't generally optimized, but are rather optimized
 //! for Growing DAG's specific use-cases.
 
+use anyhow::anyhow;
 use bimap::BiHashMap;
 use either::Either;
+use flate2::read::MultiGzDecoder;
 use never::Never;
 use petgraph::{prelude::{DiGraphMap, GraphMap}, visit::IntoEdgeReferences, Direction};
 use xxhash_rust::xxh3::Xxh3Builder;
+use rayon::prelude::*;
 use std::{
     cmp::max,
+    collections::HashMap,
     fs::File,
-    io::{self, BufRead, BufReader},
+    io::{self, BufRead, BufReader, Read},
     path::Path,
-    hash::Hash
+    hash::{BuildHasher, Hash}
 };
 use thiserror::Error;
 
-use super::data::DataFactory;
+use super::{
+    data::DataFactory,
+    weight::{EdgeTypes, TypedWeight, Weight, WeightDataFactory},
+};
 
 #[derive(Error, Debug)]
 pub enum NetworkParsingError {
@@ -24,16 +31,37 @@ pub enum NetworkParsingError {
     Misc(#[from] io::Error),
     #[error(transparent)]
     ParseDataError(#[from] anyhow::Error),
-    #[error("line '{0}' has component size {1}, but requires {2} components (first and second interactome, then {3})")]
-    InvalidSizeError(usize, usize, usize, String),
+    #[error(
+        "line '{0}' has component size {1} split on {4:?}, but requires {2} components \
+        (first and second interactome, then {3})"
+    )]
+    InvalidSizeError(usize, usize, usize, String, char),
     #[error("id factory couldn't produce {0} at line {1}.")]
     FactoryOut(String, usize),
+    #[error("line {2} repeats the edge {0} -> {1}")]
+    DuplicateEdge(String, String, usize),
 }
 
 #[derive(Debug, Error)]
 #[error("Node {0} is not present in this network.")]
 pub struct NetworkIndexError(pub String);
 
+#[derive(Debug, Error)]
+#[error("Node {0} has no name in this network.")]
+pub struct UnnamedNodeError(pub usize);
+
+#[derive(Debug, Error)]
+#[error("Node {0} already exists in this network.")]
+pub struct NetworkNameTakenError(pub String);
+
+#[derive(Debug, Error)]
+pub enum ContractNodesError {
+    #[error(transparent)]
+    NetworkIndexError(#[from] NetworkIndexError),
+    #[error(transparent)]
+    NetworkNameTakenError(#[from] NetworkNameTakenError),
+}
+
 /// A network.
 /// This is a wrapper struct around some directed graph
 /// and an id map which mapes gene names to numeric ids, since post-processing of genome names
@@ -48,143 +76,455 @@ pub struct Network<E, S: Eq + Hash> {
     pub id_map: BiHashMap<String, usize>,
     /// The max-size id
     max_id: usize,
+    /// Per-node weights keyed by node id, read from `Self::attach_node_weights`.
+    pub node_weights: HashMap<usize, f64>,
+    /// The weight of any node not present in `node_weights`.
+    pub default_node_weight: f64,
+    /// Metadata read from the interactome's `#@key value` lines (see `METADATA_PREFIX`), such as its `version`.
+    pub metadata: HashMap<String, String>,
+}
+
+/// Comments starting with this prefix are read into `Network::metadata` as `key value` pairs, rather than skipped.
+pub const METADATA_PREFIX: &str = "#@";
+
+/// Opens `path` for reading, transparently decompressing it (as it is read) if it ends in `.gz`.
+fn open(path: &Path) -> io::Result<Box<dyn BufRead>> {
+    let file = File::open(path)?;
+    Ok(if path.extension().is_some_and(|extension| extension == "gz") {
+        Box::new(BufReader::new(MultiGzDecoder::new(file)))
+    } else {
+        Box::new(BufReader::new(file))
+    })
+}
+
+/// Whether a line is neither blank nor a comment.
+fn is_content_line(line: &str) -> bool {
+    !line.is_empty() && !line.starts_with("#")
+}
+
+/// Reads the `key value` pair out of a metadata line starting with `prefix`, where the value is everything after
+/// the first run of whitespace (and may be empty). Other lines produce `None`.
+pub fn parse_metadata_line<'a>(line: &'a str, prefix: &str) -> Option<(&'a str, &'a str)> {
+    let metadata = line.strip_prefix(prefix)?.trim();
+    if metadata.is_empty() {
+        return None;
+    }
+
+    Some(match metadata.split_once(char::is_whitespace) {
+        Some((key, value)) => (key, value.trim_start()),
+        None => (metadata, ""),
+    })
+}
+
+/// The column delimiter of interactome files, unless another is passed to `Network::from_lines_delimited`.
+pub const DEFAULT_DELIMITER: char = '\t';
+
+/// How an interactome's lines are read, shared by every `Network` parser (see `Network::from_lines_with_options`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// The column delimiter, `DEFAULT_DELIMITER` unless set.
+    pub delimiter: char,
+    /// Whether the first line which is neither blank nor a comment is a header to skip.
+    pub skip_header: bool,
+    /// How an edge listed more than once is handled.
+    pub duplicates: DuplicateEdgePolicy,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self { delimiter: DEFAULT_DELIMITER, skip_header: false, duplicates: DuplicateEdgePolicy::default() }
+    }
+}
+
+/// Splits a single interactome line on `delimiter` into its source name, target name, and parsed data.
+/// Blank lines and comments produce `None`. `idx` is the 0-indexed position of the line.
+fn parse_line<'a, E, F: DataFactory<E>>(
+    factory: &F,
+    idx: usize,
+    line: &'a str,
+    delimiter: char,
+) -> Result<Option<(&'a str, &'a str, E)>, NetworkParsingError> {
+    // skip newlines and comments
+    if !is_content_line(line) {
+        return Ok(None);
+    }
+
+    let components = line.split(delimiter).collect::<Vec<_>>();
+    let expected = 2 + factory.len();
+    if components.len() < expected || (components.len() > expected && !factory.allows_extra_components()) {
+        // Transform the line position to match the file.
+        return Err(NetworkParsingError::InvalidSizeError(
+            idx + 1,
+            components.len(),
+            expected,
+            factory.err_str(),
+            delimiter,
+        ));
+    }
+
+    let source_interactome_name = components[0];
+    let target_interactome_name = components[1];
+    let data = factory.from_strs(
+        idx,
+        components
+            .into_iter()
+            .skip(2)
+            .take(factory.len())
+            .map(|s| s.to_string())
+            .collect(),
+    )
+    .map_err(NetworkParsingError::ParseDataError)?;
+
+    Ok(Some((source_interactome_name, target_interactome_name, data)))
 }
 
 impl<E: Clone, S: Eq + Hash + Copy + Ord> Network<E, S> {
+    /// Parses a network from the lines of an interactome, allocating the ids of new nodes through `id_factory`.
+    /// If `skip_header` is set, the first line which is neither blank nor a comment is skipped.
+    ///
+    /// Genes which are already in `id_map` (see `Self::load_id_map`) keep their id, while `id_factory` is offered
+    /// the id after the largest one in use for new genes. Genes in `id_map` which are not in the interactome
+    /// are dropped from the parsed network's id_map, but their ids are never reused.
     pub fn from_lines_over_id_map<
+        F: DataFactory<E> + Default,
+        I: Iterator<Item = Result<String, io::Error>>,
+    >(
+        interactome_lines: I,
+        id_map: BiHashMap<String, usize>,
+        id_factory: impl Fn(String, usize) -> Option<usize>,
+        skip_header: bool,
+    ) -> Result<Self, NetworkParsingError>
+    where
+        E: MergeableData,
+    {
+        let options = ParseOptions { skip_header, ..ParseOptions::default() };
+        Self::from_lines_with_options(interactome_lines, &F::default(), id_map, id_factory, options)
+    }
+
+    /// Like `Self::from_lines_over_id_map`, but splitting lines on `delimiter` rather than tabs.
+    pub fn from_lines_delimited_over_id_map<
+        F: DataFactory<E> + Default,
+        I: Iterator<Item = Result<String, io::Error>>,
+    >(
+        interactome_lines: I,
+        id_map: BiHashMap<String, usize>,
+        id_factory: impl Fn(String, usize) -> Option<usize>,
+        skip_header: bool,
+        delimiter: char,
+    ) -> Result<Self, NetworkParsingError>
+    where
+        E: MergeableData,
+    {
+        let options = ParseOptions { delimiter, skip_header, ..ParseOptions::default() };
+        Self::from_lines_with_options(interactome_lines, &F::default(), id_map, id_factory, options)
+    }
+
+    /// The parser behind every `from_lines` and `from_file` function, which reads lines as `options` describes,
+    /// parses their data with `factory`, and allocates ids over `id_map` as `Self::from_lines_over_id_map` does.
+    /// The other functions use `F::default()`, so factories configured at runtime (such as
+    /// `ColumnWeightDataFactory`) are passed here.
+    pub fn from_lines_with_options<
         F: DataFactory<E>,
         I: Iterator<Item = Result<String, io::Error>>,
     >(
         interactome_lines: I,
-        mut id_map: BiHashMap<String, usize>,
+        factory: &F,
+        id_map: BiHashMap<String, usize>,
         id_factory: impl Fn(String, usize) -> Option<usize>,
-    ) -> Result<Self, NetworkParsingError> {
-        let mut graph = DiGraphMap::new();
-        let mut max_id = 0;
+        options: ParseOptions,
+    ) -> Result<Self, NetworkParsingError>
+    where
+        E: MergeableData,
+    {
+        let mut network = Self::empty_over_id_map(id_map);
+        let mut duplicates = DuplicateEdges::new(options.duplicates);
 
+        // Without `skip_header`, the first content line may still be an unexpected header.
+        let mut maybe_header = !options.skip_header;
+        let mut skip_header = options.skip_header;
         for (idx, line) in interactome_lines.enumerate() {
             let line = line?;
 
-            // newlines
-            if line.is_empty() {
+            if skip_header && is_content_line(&line) {
+                log::info!("Skipping header on line {}: {line}", idx + 1);
+                skip_header = false;
                 continue;
             }
 
-            // and comments
-            if line.starts_with("#") {
+            if let Some((key, value)) = parse_metadata_line(&line, METADATA_PREFIX) {
+                network.metadata.insert(key.to_string(), value.to_string());
                 continue;
             }
 
-            let components = line.split('\t').collect::<Vec<_>>();
-            if components.len() != 2 + F::len() {
-                // Transform the line position to match the file.
-                return Err(NetworkParsingError::InvalidSizeError(
-                    idx + 1,
-                    components.len(),
-                    2 + F::len(),
-                    F::err_str(),
-                ));
+            let parsed = parse_line(factory, idx, &line, options.delimiter).map_err(|err| match err {
+                NetworkParsingError::ParseDataError(err) if maybe_header => NetworkParsingError::ParseDataError(
+                    anyhow!("{err}. If this line is a header, skip it with `skip_header` (`--has-header`)."),
+                ),
+                err => err,
+            })?;
+            maybe_header &= !is_content_line(&line);
+
+            if let Some((source, target, data)) = parsed {
+                network.insert_parsed_edge((source, target, data), idx + 1, &id_factory, &mut duplicates)?;
             }
+        }
 
-            let source_interactome_name = components[0];
-            let target_interactome_name = components[1];
-            let data = F::from_strs(
-                idx,
-                components
-                    .into_iter()
-                    .map(|s| s.to_string())
-                    .skip(2)
-                    .collect(),
-            )
-            .map_err(NetworkParsingError::ParseDataError)?;
-
-            let source_interactome = id_map
-                .get_by_left(source_interactome_name)
-                .copied()
-                .or_else(|| {
-                    id_factory(source_interactome_name.to_string(), id_map.len()).inspect(|&idx| {
-                        let _ = graph.add_node(Either::Left(idx));
-                        id_map.insert(source_interactome_name.to_string(), idx);
-                        max_id = max(idx, max_id);
-                    })
-                })
-                .ok_or_else(|| {
-                    NetworkParsingError::FactoryOut(
-                        source_interactome_name.to_string(),
-                        id_map.len(),
-                    )
-                })?;
-
-            let target_interactome = id_map
-                .get_by_left(target_interactome_name)
-                .copied()
-                .or_else(|| {
-                    id_factory(target_interactome_name.to_string(), id_map.len()).inspect(|&idx| {
-                        let _ = graph.add_node(Either::Left(idx));
-                        id_map.insert(target_interactome_name.to_string(), idx);
-                        max_id = max(idx, max_id);
-                    })
-                })
-                .ok_or_else(|| {
-                    NetworkParsingError::FactoryOut(
-                        target_interactome_name.to_string(),
-                        id_map.len(),
-                    )
-                })?;
+        Ok(network.without_unused_ids())
+    }
 
-            graph.add_edge(Either::Left(source_interactome), Either::Left(target_interactome), data);
+    /// An empty network, which allocates ids after the largest one in `id_map`.
+    fn empty_over_id_map(id_map: BiHashMap<String, usize>) -> Self {
+        Self {
+            max_id: id_map.right_values().copied().max().unwrap_or(0),
+            id_map,
+            graph: DiGraphMap::new(),
+            node_weights: HashMap::new(),
+            default_node_weight: 0.0,
+            metadata: HashMap::new(),
         }
+    }
 
-        Ok(Self {
-            id_map,
-            graph,
-            max_id,
-        })
+    /// Drops the genes of a parsed network's id_map which none of its edges use.
+    fn without_unused_ids(mut self) -> Self {
+        let graph = &self.graph;
+        self.id_map.retain(|_, id| graph.contains_node(Either::Left(*id)));
+        self
+    }
+
+    /// Reads a tab-separated `gene\tid` file, such as one written from a previous run's id_map,
+    /// to pass to `Self::from_file_over_id_map`. This keeps the ids of genes stable across runs,
+    /// regardless of the order they appear in.
+    pub fn load_id_map(path: &Path) -> Result<BiHashMap<String, usize>, NetworkParsingError> {
+        let mut id_map = BiHashMap::new();
+
+        for (idx, line) in open(path)?.lines().enumerate() {
+            let line = line?;
+            if !is_content_line(&line) {
+                continue;
+            }
+
+            let [gene, id] = line.split('\t').collect::<Vec<_>>()[..] else {
+                return Err(NetworkParsingError::ParseDataError(anyhow!(
+                    "Line {} of the id map should contain a gene and its id",
+                    idx + 1
+                )));
+            };
+            let id = id
+                .trim()
+                .parse::<usize>()
+                .map_err(|err| anyhow!("Line {} of the id map has an invalid id: {err}", idx + 1))?;
+
+            if id_map.insert_no_overwrite(gene.to_string(), id).is_err() {
+                return Err(NetworkParsingError::ParseDataError(anyhow!(
+                    "Line {} of the id map repeats the gene {gene} or the id {id}",
+                    idx + 1
+                )));
+            }
+        }
+
+        Ok(id_map)
+    }
+
+    /// Resolves the id of `name`, allocating one through `id_factory` if it has not been seen yet.
+    fn resolve_or_allocate(
+        &mut self,
+        name: &str,
+        id_factory: &impl Fn(String, usize) -> Option<usize>,
+    ) -> Result<usize, NetworkParsingError> {
+        self.id_map
+            .get_by_left(name)
+            .copied()
+            .or_else(|| {
+                let next_id = if self.id_map.is_empty() { 0 } else { self.max_id + 1 };
+                id_factory(name.to_string(), next_id).inspect(|&idx| {
+                    let _ = self.graph.add_node(Either::Left(idx));
+                    self.id_map.insert(name.to_string(), idx);
+                    self.max_id = max(idx, self.max_id);
+                })
+            })
+            .ok_or_else(|| NetworkParsingError::FactoryOut(name.to_string(), self.id_map.len()))
+    }
+
+    /// Inserts an edge parsed from the 1-indexed `line`, merging it into an edge which already exists
+    /// by `duplicates`.
+    fn insert_parsed_edge(
+        &mut self,
+        (source_name, target_name, data): (&str, &str, E),
+        line: usize,
+        id_factory: &impl Fn(String, usize) -> Option<usize>,
+        duplicates: &mut DuplicateEdges,
+    ) -> Result<(), NetworkParsingError>
+    where
+        E: MergeableData,
+    {
+        let source = self.resolve_or_allocate(source_name, id_factory)?;
+        let target = self.resolve_or_allocate(target_name, id_factory)?;
+        let (source, target) = (Either::Left(source), Either::Left(target));
+
+        let data = match self.graph.edge_weight(source, target) {
+            Some(old) => duplicates.merge((source_name, target_name), line, old.clone(), data)?,
+            None => data,
+        };
+        self.graph.add_edge(source, target, data);
+
+        Ok(())
     }
 
     pub fn from_lines_using_id_map<
-        F: DataFactory<E>,
+        F: DataFactory<E> + Default,
         I: Iterator<Item = Result<String, io::Error>>,
     >(
         interactome_lines: I,
         id_map: &BiHashMap<String, usize>,
-    ) -> Result<Self, NetworkParsingError> {
+        skip_header: bool,
+    ) -> Result<Self, NetworkParsingError>
+    where
+        E: MergeableData,
+    {
         Self::from_lines_over_id_map::<F, _>(
             interactome_lines,
             BiHashMap::new(),
             |str_identifier, _| id_map.get_by_left(&str_identifier).copied(),
+            skip_header,
         )
     }
 
-    pub fn from_lines<F: DataFactory<E>, I: Iterator<Item = Result<String, io::Error>>>(
+    pub fn from_lines<F: DataFactory<E> + Default, I: Iterator<Item = Result<String, io::Error>>>(
         interactome_lines: I,
-    ) -> Result<Self, NetworkParsingError> {
-        Self::from_lines_over_id_map::<F, _>(interactome_lines, BiHashMap::new(), |_, idx| {
-            Some(idx)
-        })
+    ) -> Result<Self, NetworkParsingError>
+    where
+        E: MergeableData,
+    {
+        Self::from_lines_over_id_map::<F, _>(
+            interactome_lines,
+            BiHashMap::new(),
+            |_, idx| Some(idx),
+            false,
+        )
+    }
+
+    /// Like `Self::from_lines`, but splitting lines on `delimiter` (such as `,` for CSV) rather than tabs.
+    pub fn from_lines_delimited<F: DataFactory<E> + Default, I: Iterator<Item = Result<String, io::Error>>>(
+        interactome_lines: I,
+        delimiter: char,
+    ) -> Result<Self, NetworkParsingError>
+    where
+        E: MergeableData,
+    {
+        Self::from_lines_delimited_over_id_map::<F, _>(
+            interactome_lines,
+            BiHashMap::new(),
+            |_, idx| Some(idx),
+            false,
+            delimiter,
+        )
+    }
+
+    /// Like `Self::from_lines`, but for in-memory lines which can't fail to be read.
+    ///
+    /// ```
+    /// use growing_dags::parsing::{network::Network, weight::WeightDataFactory};
+    ///
+    /// let lines = vec!["A\tB\t0.5".to_string(), "B\tC\t0.25".to_string()];
+    /// let network = Network::<_, ()>::from_lines_infallible::<WeightDataFactory>(lines.into_iter()).unwrap();
+    ///
+    /// assert_eq!(network.edge_count(), 2);
+    /// assert_eq!(network.edge_weight_by_name("B", "C").unwrap().unwrap().0, 0.25);
+    /// ```
+    pub fn from_lines_infallible<F: DataFactory<E> + Default>(
+        interactome_lines: impl Iterator<Item = String>,
+    ) -> Result<Self, NetworkParsingError>
+    where
+        E: MergeableData,
+    {
+        Self::from_lines::<F, _>(interactome_lines.map(Ok))
     }
 
-    pub fn from_file_over_id_map<F: DataFactory<E>>(
+    /// Like `Self::from_lines_over_id_map`, over the lines of a file. Files ending in `.gz` are decompressed
+    /// as they are read, and this holds for every other `from_file` function too.
+    pub fn from_file_over_id_map<F: DataFactory<E> + Default>(
         interactome: &Path,
         id_map: BiHashMap<String, usize>,
         id_factory: impl Fn(String, usize) -> Option<usize>,
-    ) -> Result<Self, NetworkParsingError> {
-        let lines = BufReader::new(File::open(interactome)?).lines();
+        skip_header: bool,
+    ) -> Result<Self, NetworkParsingError>
+    where
+        E: MergeableData,
+    {
+        let options = ParseOptions { skip_header, ..ParseOptions::default() };
+        Self::from_file_with_options(interactome, &F::default(), id_map, id_factory, options)
+    }
 
-        Self::from_lines_over_id_map::<F, _>(lines, id_map, id_factory)
+    /// Like `Self::from_lines_with_options`, over the lines of a file.
+    pub fn from_file_with_options<F: DataFactory<E>>(
+        interactome: &Path,
+        factory: &F,
+        id_map: BiHashMap<String, usize>,
+        id_factory: impl Fn(String, usize) -> Option<usize>,
+        options: ParseOptions,
+    ) -> Result<Self, NetworkParsingError>
+    where
+        E: MergeableData,
+    {
+        Self::from_lines_with_options(open(interactome)?.lines(), factory, id_map, id_factory, options)
     }
 
-    pub fn from_file_using_id_map<F: DataFactory<E>>(
+    pub fn from_file_using_id_map<F: DataFactory<E> + Default>(
         interactome: &Path,
         id_map: &BiHashMap<String, usize>,
-    ) -> Result<Self, NetworkParsingError> {
-        Self::from_file_over_id_map::<F>(interactome, BiHashMap::new(), |str_identifier, _| {
-            id_map.get_by_left(&str_identifier).copied()
-        })
+        skip_header: bool,
+    ) -> Result<Self, NetworkParsingError>
+    where
+        E: MergeableData,
+    {
+        Self::from_file_over_id_map::<F>(
+            interactome,
+            BiHashMap::new(),
+            |str_identifier, _| id_map.get_by_left(&str_identifier).copied(),
+            skip_header,
+        )
+    }
+
+    pub fn from_file<F: DataFactory<E> + Default>(interactome: &Path) -> Result<Self, NetworkParsingError>
+    where
+        E: MergeableData,
+    {
+        Self::from_file_over_id_map::<F>(interactome, BiHashMap::new(), |_, idx| Some(idx), false)
     }
 
-    pub fn from_file<F: DataFactory<E>>(interactome: &Path) -> Result<Self, NetworkParsingError> {
-        Self::from_file_over_id_map::<F>(interactome, BiHashMap::new(), |_, idx| Some(idx))
+    /// Like `Self::from_file`, but splitting lines on `delimiter` rather than tabs.
+    pub fn from_file_delimited<F: DataFactory<E> + Default>(
+        interactome: &Path,
+        delimiter: char,
+    ) -> Result<Self, NetworkParsingError>
+    where
+        E: MergeableData,
+    {
+        Self::from_lines_delimited::<F, _>(open(interactome)?.lines(), delimiter)
+    }
+
+    /// Like `Self::from_lines`, but handling edges which are listed more than once by `policy`,
+    /// rather than always keeping the last weight.
+    pub fn from_lines_with_policy<F: DataFactory<E> + Default, I: Iterator<Item = Result<String, io::Error>>>(
+        interactome_lines: I,
+        policy: DuplicateEdgePolicy,
+    ) -> Result<Self, NetworkParsingError>
+    where
+        E: MergeableData,
+    {
+        let options = ParseOptions { duplicates: policy, ..ParseOptions::default() };
+        Self::from_lines_with_options(interactome_lines, &F::default(), BiHashMap::new(), |_, idx| Some(idx), options)
+    }
+
+    /// Like `Self::from_lines_with_policy`, over the lines of a file.
+    pub fn from_file_with_policy<F: DataFactory<E> + Default>(
+        interactome: &Path,
+        policy: DuplicateEdgePolicy,
+    ) -> Result<Self, NetworkParsingError>
+    where
+        E: MergeableData,
+    {
+        Self::from_lines_with_policy::<F, _>(open(interactome)?.lines(), policy)
     }
 
     /// Gets a node index from a string (gene). The inverse of `Self::id_from_idx`.
@@ -195,14 +535,74 @@ impl<E: Clone, S: Eq + Hash + Copy + Ord> Network<E, S> {
             .copied()
     }
 
+    /// The number of nodes in this network, including any super-nodes.
+    pub fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+
+    /// The number of edges in this network, including any super-node edges.
+    pub fn edge_count(&self) -> usize {
+        self.graph.edge_count()
+    }
+
     /// Allocates a new id for a node.
-    /// Prefer this over `self.graph.add_node`.
+    /// Prefer this over `self.graph.add_node`. Note that the node will have no name:
+    /// if it needs to be resolved through `Self::get_node`, use `Self::add_node_with_name`.
     pub fn add_node(&mut self) -> usize {
         let _ = self.graph.add_node(Either::Left(self.max_id + 1));
         self.max_id += 1;
         self.max_id
     }
 
+    /// Allocates a new id for a node (as in `Self::add_node`), registering it under `name`.
+    pub fn add_node_with_name(&mut self, name: &str) -> Result<usize, NetworkNameTakenError> {
+        if self.id_map.contains_left(name) {
+            return Err(NetworkNameTakenError(name.to_string()));
+        }
+
+        let id = self.add_node();
+        self.id_map.insert(name.to_string(), id);
+
+        Ok(id)
+    }
+
+    /// Adds an edge `a -> b` between two existing nodes, resolved by name.
+    ///
+    /// As with duplicate lines in a parsed interactome, adding an edge which already exists
+    /// overwrites its weight. See `Self::add_edge_by_name_allocating` to add edges between new genes.
+    pub fn add_edge_by_name(&mut self, a: &str, b: &str, weight: E) -> Result<(), NetworkIndexError> {
+        let a = self.get_node(a)?;
+        let b = self.get_node(b)?;
+
+        self.graph.add_edge(Either::Left(a), Either::Left(b), weight);
+
+        Ok(())
+    }
+
+    /// Adds an edge `a -> b` (as in `Self::add_edge_by_name`), allocating named nodes
+    /// (as in `Self::add_node_with_name`) for genes which are not yet in this network.
+    /// Returns the ids of `a` and `b`.
+    pub fn add_edge_by_name_allocating(&mut self, a: &str, b: &str, weight: E) -> (usize, usize) {
+        let mut resolve = |name: &str| match self.get_node(name) {
+            Ok(id) => id,
+            Err(_) => self.add_node_with_name(name).expect("the name was just checked to be free"),
+        };
+        let a = resolve(a);
+        let b = resolve(b);
+
+        self.graph.add_edge(Either::Left(a), Either::Left(b), weight);
+
+        (a, b)
+    }
+
+    /// Gets the weight of the edge `a -> b` by name, or `None` if there is no such edge.
+    pub fn edge_weight_by_name(&self, a: &str, b: &str) -> Result<Option<&E>, NetworkIndexError> {
+        let a = self.get_node(a)?;
+        let b = self.get_node(b)?;
+
+        Ok(self.graph.edge_weight(Either::Left(a), Either::Left(b)))
+    }
+
     pub fn as_nodes(&self, nodes: &[&str]) -> Result<Vec<Either<usize, S>>, NetworkIndexError> {
         nodes.iter().map(|node| self.get_node(node).map(Either::Left)).collect()
     }
@@ -212,15 +612,183 @@ impl<E: Clone, S: Eq + Hash + Copy + Ord> Network<E, S> {
         self.id_map.get_by_right(&id)
     }
 
+    /// Every named gene alongside its id, sorted by id, so that output over the id_map is reproducible
+    /// (unlike iterating over the id_map directly).
+    pub fn iter_names(&self) -> impl Iterator<Item = (&String, usize)> {
+        let mut names = self.id_map.iter().map(|(name, &id)| (name, id)).collect::<Vec<_>>();
+        names.sort_by_key(|&(_, id)| id);
+        names.into_iter()
+    }
+
+    /// The gene `id` for output, as in `Self::id_from_idx`. Nodes without a name (see `Self::add_node`) are written
+    /// as `[[Unnamed <id>]]`, so that their internal id is never mistaken for a numeric gene name.
+    pub fn display_name(&self, id: usize) -> String {
+        self.id_from_idx(id).cloned().unwrap_or_else(|| format!("[[Unnamed {id}]]"))
+    }
+
+    /// `node` as compared across networks by `Self::structural_eq`: genes by name, since the same gene
+    /// can be given different ids depending on line order. Unnamed nodes fall back to their id.
+    fn structural_node(&self, node: Either<usize, S>) -> Either<Result<&String, usize>, S> {
+        node.map_left(|id| self.id_from_idx(id).ok_or(id))
+    }
+
+    /// Every node (see `Self::structural_node`), sorted.
+    fn structural_nodes(&self) -> Vec<Either<Result<&String, usize>, S>> {
+        let mut nodes = self.graph.nodes().map(|node| self.structural_node(node)).collect::<Vec<_>>();
+        nodes.sort();
+        nodes
+    }
+
+    /// Every edge over `Self::structural_node`s alongside its weight, sorted by its endpoints.
+    #[allow(clippy::type_complexity)]
+    fn structural_edges(&self) -> Vec<(Either<Result<&String, usize>, S>, Either<Result<&String, usize>, S>, &E)> {
+        let mut edges = self
+            .graph
+            .all_edges()
+            .map(|(a, b, weight)| (self.structural_node(a), self.structural_node(b), weight))
+            .collect::<Vec<_>>();
+        edges.sort_by(|(a1, b1, _), (a2, b2, _)| (a1, b1).cmp(&(a2, b2)));
+        edges
+    }
+
+    /// Whether both networks have the same nodes and the same edges with the same weights, however they
+    /// were built: genes are matched by name rather than id, and the order edges were added in doesn't matter.
+    /// Node weights and metadata aren't compared.
+    pub fn structural_eq(&self, other: &Self) -> bool
+    where
+        E: PartialEq,
+    {
+        self.structural_nodes() == other.structural_nodes() && self.structural_edges() == other.structural_edges()
+    }
+
+    /// A hash of the network's nodes and edges which, like `Self::structural_eq`, doesn't depend on ids or
+    /// insertion order, so structurally equal networks hash the same. Edge weights are left out, since
+    /// floating-point weights can't be hashed: networks which only differ in weights collide.
+    pub fn structural_hash(&self) -> u64 {
+        let edges = self.structural_edges().into_iter().map(|(a, b, _)| (a, b)).collect::<Vec<_>>();
+        Xxh3Builder::new().hash_one((self.structural_nodes(), edges))
+    }
+
+    /// Reads per-node weights from a two-column `gene\tweight` file.
+    /// Genes which are not in this network are skipped with a warning, while nodes
+    /// which are not in the file fall back to `Self::default_node_weight`.
+    pub fn attach_node_weights(&mut self, path: &Path) -> Result<(), NetworkParsingError> {
+        let lines = open(path)?.lines();
+
+        for (idx, line) in lines.enumerate() {
+            let line = line?;
+
+            if line.is_empty() || line.starts_with("#") {
+                continue;
+            }
+
+            let components = line.split('\t').collect::<Vec<_>>();
+            if components.len() != 2 {
+                return Err(NetworkParsingError::ParseDataError(anyhow!(
+                    "Line {} should contain a gene and its weight, but has {} components",
+                    idx + 1,
+                    components.len()
+                )));
+            }
+
+            let weight = WeightDataFactory.from_strs(idx, vec![components[1].to_string()])?;
+            match self.id_map.get_by_left(components[0]) {
+                Some(&node) => {
+                    self.node_weights.insert(node, weight.0);
+                }
+                None => log::warn!(
+                    "Node weight file line {} references {}, which is not in the network. Skipping.",
+                    idx + 1,
+                    components[0]
+                ),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gets the weight of a node, falling back to `Self::default_node_weight`.
+    pub fn node_weight(&self, id: usize) -> f64 {
+        self.node_weights.get(&id).copied().unwrap_or(self.default_node_weight)
+    }
+
+    /// Gets the weight of a node by its name. See `Self::node_weight`.
+    pub fn node_weight_by_name(&self, node: &str) -> Result<f64, NetworkIndexError> {
+        self.get_node(node).map(|id| self.node_weight(id))
+    }
+
+    /// Formats every edge between named nodes as a tab-separated `a\tb` line, sorted by name.
+    /// Edges touching other nodes (such as super-nodes) are skipped.
+    pub fn to_edge_list(&self) -> String {
+        let mut edges = self
+            .graph
+            .all_edges()
+            .filter_map(|(a, b, _)| Some((self.id_from_idx(a.left()?)?, self.id_from_idx(b.left()?)?)))
+            .collect::<Vec<_>>();
+        edges.sort();
+
+        edges.into_iter().map(|(a, b)| format!("{a}\t{b}\n")).collect()
+    }
+
+    /// Finds pairs of distinct node names which are equal after whitespace normalization
+    /// (e.g. `"A B"` and `"A  B"`), which usually point to data-entry errors.
+    pub fn find_suspicious_duplicates(&self) -> Vec<(String, String)> {
+        let mut normalized: HashMap<String, Vec<&String>> = HashMap::new();
+        for (name, _) in self.id_map.iter() {
+            normalized
+                .entry(name.split_whitespace().collect::<Vec<_>>().join(" "))
+                .or_default()
+                .push(name);
+        }
+
+        let mut duplicates = vec![];
+        for mut names in normalized.into_values() {
+            names.sort();
+            for (idx, a) in names.iter().enumerate() {
+                for b in &names[idx + 1..] {
+                    duplicates.push((a.to_string(), b.to_string()));
+                }
+            }
+        }
+
+        duplicates.sort();
+        duplicates
+    }
+
+    /// The `(gene, in-degree, out-degree)` of every named node, sorted by total degree (highest first)
+    /// and then by name. Super-nodes are skipped, though edges to them are counted.
+    pub fn degree_table(&self) -> Vec<(String, usize, usize)> {
+        let mut degrees = self
+            .id_map
+            .iter()
+            .filter(|(_, &id)| self.graph.contains_node(Either::Left(id)))
+            .map(|(name, &id)| {
+                let node = Either::Left(id);
+                (
+                    name.clone(),
+                    self.graph.neighbors_directed(node, Direction::Incoming).count(),
+                    self.graph.neighbors_directed(node, Direction::Outgoing).count(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        degrees.sort_by(|(a, a_in, a_out), (b, b_in, b_out)| {
+            (b_in + b_out).cmp(&(a_in + a_out)).then_with(|| a.cmp(b))
+        });
+        degrees
+    }
+
     /// Removes edges {direction} from {nodes}. For example, remove
     /// incoming edges from source nodes. If you want to remove a set of nodes instead,
     /// look at `Self::remove`.
+    ///
+    /// Returns the removed edges by name, in the order they were removed.
     pub fn prune(
         &mut self,
         nodes: &[String],
         direction: Direction,
         require_nodes: bool,
-    ) -> Result<(), NetworkIndexError> {
+    ) -> Result<Vec<(String, String)>, NetworkIndexError> {
         let mut pooled_edges = vec![];
         for node in nodes {
             let node_id = self.id_map.get_by_left(node);
@@ -235,11 +803,21 @@ impl<E: Clone, S: Eq + Hash + Copy + Ord> Network<E, S> {
             }
         }
 
+        let mut removed = vec![];
         for (a, b) in pooled_edges {
-            self.graph.remove_edge(a, b);
+            if self.graph.remove_edge(a, b).is_none() {
+                continue;
+            }
+
+            if let (Some(a), Some(b)) = (
+                a.left().and_then(|a| self.id_from_idx(a)),
+                b.left().and_then(|b| self.id_from_idx(b)),
+            ) {
+                removed.push((a.to_string(), b.to_string()));
+            }
         }
 
-        Ok(())
+        Ok(removed)
     }
 
     pub fn destroy_right_nodes(self) -> Network<E, Never> {
@@ -260,7 +838,10 @@ impl<E: Clone, S: Eq + Hash + Copy + Ord> Network<E, S> {
         Network {
             graph: new_graph,
             id_map: self.id_map,
-            max_id: self.max_id
+            max_id: self.max_id,
+            node_weights: self.node_weights,
+            default_node_weight: self.default_node_weight,
+            metadata: self.metadata,
         }
     }
 
@@ -270,6 +851,473 @@ impl<E: Clone, S: Eq + Hash + Copy + Ord> Network<E, S> {
     }
 }
 
+impl<E: Clone + Send, S: Eq + Hash + Copy + Ord> Network<E, S> {
+    /// Parses an interactome file in parallel, producing the same network as `Self::from_file`.
+    ///
+    /// The file is split into byte ranges aligned to line boundaries, each of which is parsed
+    /// on its own thread. The parsed edges are then merged in file order, so ids are allocated
+    /// in the same order (and later duplicate edges overwrite earlier ones) as the serial parser.
+    /// Gzipped files are decompressed in full before being split.
+    pub fn from_file_parallel<F: DataFactory<E> + Default + Sync>(
+        interactome: &Path,
+        skip_header: bool,
+    ) -> Result<Self, NetworkParsingError>
+    where
+        E: MergeableData,
+    {
+        let options = ParseOptions { skip_header, ..ParseOptions::default() };
+        let factory = F::default();
+        Self::from_file_parallel_with_options(interactome, &factory, BiHashMap::new(), |_, idx| Some(idx), options)
+    }
+
+    /// Like `Self::from_file_with_options`, but parsing in parallel as `Self::from_file_parallel` does.
+    /// Only parsing is parallel: ids are allocated and duplicate edges merged in file order afterwards.
+    pub fn from_file_parallel_with_options<F: DataFactory<E> + Sync>(
+        interactome: &Path,
+        factory: &F,
+        id_map: BiHashMap<String, usize>,
+        id_factory: impl Fn(String, usize) -> Option<usize>,
+        options: ParseOptions,
+    ) -> Result<Self, NetworkParsingError>
+    where
+        E: MergeableData,
+    {
+        let mut contents = String::new();
+        open(interactome)?.read_to_string(&mut contents)?;
+
+        Self::from_str_parallel(&contents, rayon::current_num_threads(), factory, id_map, id_factory, options)
+    }
+
+    fn from_str_parallel<F: DataFactory<E> + Sync>(
+        contents: &str,
+        chunk_count: usize,
+        factory: &F,
+        id_map: BiHashMap<String, usize>,
+        id_factory: impl Fn(String, usize) -> Option<usize>,
+        options: ParseOptions,
+    ) -> Result<Self, NetworkParsingError>
+    where
+        E: MergeableData,
+    {
+        let bytes = contents.as_bytes();
+        let chunk_size = bytes.len().div_ceil(chunk_count.max(1)).max(1);
+
+        // The header is found up-front, as chunks can't know whether a previous chunk had one.
+        // Metadata lines above it are still read, as the serial parser does.
+        let mut start = 0;
+        let mut line_offset = 0;
+        let mut header_metadata = vec![];
+        if options.skip_header {
+            for line in contents.split_inclusive('\n') {
+                start += line.len();
+                line_offset += 1;
+
+                let line = line.trim_end_matches(['\r', '\n']);
+                if is_content_line(line) {
+                    log::info!("Skipping header on line {line_offset}: {}", line.trim_end());
+                    break;
+                }
+                header_metadata.extend(parse_metadata_line(line, METADATA_PREFIX));
+            }
+        }
+
+        // Split the contents into chunks, pushing each chunk end forward to the next line boundary.
+        let mut chunks = vec![];
+        while start < bytes.len() {
+            let end = (start + chunk_size).min(bytes.len());
+            let end = bytes[end..]
+                .iter()
+                .position(|&byte| byte == b'\n')
+                .map_or(bytes.len(), |offset| end + offset + 1);
+            chunks.push(&contents[start..end]);
+            start = end;
+        }
+
+        // The line number each chunk starts at, for error reporting.
+        let mut line_offsets = Vec::with_capacity(chunks.len());
+        for chunk in &chunks {
+            line_offsets.push(line_offset);
+            line_offset += chunk.bytes().filter(|&byte| byte == b'\n').count();
+        }
+
+        let parsed_chunks = chunks
+            .par_iter()
+            .zip(line_offsets.par_iter())
+            .map(|(chunk, &line_offset)| {
+                let (mut edges, mut metadata) = (vec![], vec![]);
+                for (idx, line) in chunk.lines().enumerate() {
+                    if let Some(pair) = parse_metadata_line(line, METADATA_PREFIX) {
+                        metadata.push(pair);
+                    } else if let Some(edge) = parse_line(factory, line_offset + idx, line, options.delimiter)? {
+                        edges.push((line_offset + idx + 1, edge));
+                    }
+                }
+                Ok((edges, metadata))
+            })
+            .collect::<Vec<Result<(Vec<_>, Vec<_>), NetworkParsingError>>>();
+
+        let mut network = Self::empty_over_id_map(id_map);
+        for (key, value) in header_metadata {
+            network.metadata.insert(key.to_string(), value.to_string());
+        }
+        let mut duplicates = DuplicateEdges::new(options.duplicates);
+        for chunk in parsed_chunks {
+            let (edges, metadata) = chunk?;
+            for (key, value) in metadata {
+                network.metadata.insert(key.to_string(), value.to_string());
+            }
+            for (line, edge) in edges {
+                network.insert_parsed_edge(edge, line, &id_factory, &mut duplicates)?;
+            }
+        }
+
+        Ok(network.without_unused_ids())
+    }
+}
+
+/// How a parser handles an edge listed more than once (see `ParseOptions::duplicates`).
+///
+/// Weights are compared after the `DataFactory` has transformed them, so with `LogWeightDataFactory`, `KeepMin`
+/// keeps the lowest cost, which is the highest raw weight. Data without a weight is always overwritten,
+/// unless the policy is `Error`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicateEdgePolicy {
+    /// Keep the last weight.
+    #[default]
+    Overwrite,
+    KeepMin,
+    KeepMax,
+    /// The mean of every weight the edge was listed with.
+    Mean,
+    /// Fail with `NetworkParsingError::DuplicateEdge`.
+    Error,
+}
+
+/// Edge data which a `DuplicateEdgePolicy` can compare and average by its weight.
+pub trait MergeableData: Clone {
+    fn weight(&self) -> Option<f64>;
+    fn set_weight(&mut self, weight: f64);
+}
+
+impl MergeableData for () {
+    fn weight(&self) -> Option<f64> {
+        None
+    }
+
+    fn set_weight(&mut self, _weight: f64) {}
+}
+
+impl MergeableData for Weight {
+    fn weight(&self) -> Option<f64> {
+        Some(self.0)
+    }
+
+    fn set_weight(&mut self, weight: f64) {
+        self.0 = weight;
+    }
+}
+
+/// Edges keep the type of whichever listing the policy keeps (or the last one, for `DuplicateEdgePolicy::Mean`).
+impl MergeableData for TypedWeight {
+    fn weight(&self) -> Option<f64> {
+        Some(self.weight.0)
+    }
+
+    fn set_weight(&mut self, weight: f64) {
+        self.weight.0 = weight;
+    }
+}
+
+/// Applies a `DuplicateEdgePolicy` while parsing, counting how often each edge was listed for the mean.
+struct DuplicateEdges {
+    policy: DuplicateEdgePolicy,
+    counts: HashMap<(String, String), usize>,
+}
+
+impl DuplicateEdges {
+    fn new(policy: DuplicateEdgePolicy) -> Self {
+        Self { policy, counts: HashMap::new() }
+    }
+
+    /// Merges `new`, listed on the 1-indexed `line`, into the `old` data of the edge `source -> target`.
+    fn merge<E: MergeableData>(
+        &mut self,
+        (source, target): (&str, &str),
+        line: usize,
+        old: E,
+        mut new: E,
+    ) -> Result<E, NetworkParsingError> {
+        if self.policy == DuplicateEdgePolicy::Error {
+            return Err(NetworkParsingError::DuplicateEdge(source.to_string(), target.to_string(), line));
+        }
+        let (Some(old_weight), Some(new_weight)) = (old.weight(), new.weight()) else {
+            return Ok(new);
+        };
+
+        Ok(match self.policy {
+            DuplicateEdgePolicy::Overwrite | DuplicateEdgePolicy::Error => new,
+            DuplicateEdgePolicy::KeepMin if old_weight <= new_weight => old,
+            DuplicateEdgePolicy::KeepMax if old_weight >= new_weight => old,
+            DuplicateEdgePolicy::KeepMin | DuplicateEdgePolicy::KeepMax => new,
+            DuplicateEdgePolicy::Mean => {
+                let count = self.counts.entry((source.to_string(), target.to_string())).or_insert(1);
+                *count += 1;
+                new.set_weight(old_weight + (new_weight - old_weight) / *count as f64);
+                new
+            }
+        })
+    }
+}
+
+/// How `Network::from_replicate_files` combines an edge's weights across replicates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Aggregator {
+    Mean,
+    /// The median, averaging the middle two weights for an even number of replicates.
+    Median,
+}
+
+impl Aggregator {
+    fn aggregate(self, weights: &mut [f64]) -> f64 {
+        match self {
+            Aggregator::Mean => weights.iter().sum::<f64>() / weights.len() as f64,
+            Aggregator::Median => {
+                weights.sort_by(f64::total_cmp);
+                let middle = weights.len() / 2;
+                if weights.len().is_multiple_of(2) {
+                    (weights[middle - 1] + weights[middle]) / 2.0
+                } else {
+                    weights[middle]
+                }
+            }
+        }
+    }
+}
+
+impl<S: Eq + Hash + Copy + Ord> Network<Weight, S> {
+    /// Combines the same interactome measured across several replicate files, keeping the edges which appear in
+    /// at least `min_support` of them, weighted by the `aggregator` of their weights. Nodes are allocated ids
+    /// in the order their edges are first seen.
+    pub fn from_replicate_files<F: DataFactory<Weight> + Default>(
+        paths: &[&Path],
+        min_support: usize,
+        aggregator: Aggregator,
+    ) -> Result<Self, NetworkParsingError> {
+        let mut edges: Vec<((String, String), Vec<f64>)> = vec![];
+        let mut edge_indices = HashMap::new();
+        for path in paths {
+            let replicate = Network::<Weight, S>::from_file::<F>(path)?;
+            for (a, b, weight) in replicate.graph.all_edges() {
+                let (Some(a), Some(b)) = (
+                    a.left().and_then(|a| replicate.id_from_idx(a)),
+                    b.left().and_then(|b| replicate.id_from_idx(b)),
+                ) else {
+                    continue;
+                };
+
+                let edge = (a.clone(), b.clone());
+                let idx = *edge_indices.entry(edge.clone()).or_insert_with(|| {
+                    edges.push((edge, vec![]));
+                    edges.len() - 1
+                });
+                edges[idx].1.push(weight.0);
+            }
+        }
+
+        let mut network = Self::from_lines_infallible::<WeightDataFactory>(std::iter::empty())?;
+        for ((a, b), mut weights) in edges {
+            if weights.len() < min_support {
+                continue;
+            }
+
+            network.add_edge_by_name_allocating(&a, &b, Weight(aggregator.aggregate(&mut weights)));
+        }
+
+        Ok(network)
+    }
+
+    /// Whether any edge has a negative weight, which path-finding with Dijkstra can't handle.
+    pub fn has_negative_weights(&self) -> bool {
+        self.graph.all_edges().any(|(_, _, weight)| weight.0 < 0.0)
+    }
+
+    /// Finds a cycle whose weights sum to less than zero, under which shortest paths are ill-defined, returning its nodes
+    /// in order (without repeating the first). This runs Bellman-Ford from every node at once, so it takes `O(VE)`:
+    /// only call it when `Self::has_negative_weights`.
+    pub fn find_negative_cycle(&self) -> Option<Vec<Either<usize, S>>> {
+        let mut distances = self.graph.nodes().map(|node| (node, 0_f64)).collect::<HashMap<_, _>>();
+        let mut parents = HashMap::new();
+
+        // As in `bellman_ford_paths`, only a negative cycle lets the |V|th round relax an edge.
+        let mut relaxed = None;
+        for _ in 0..self.graph.node_count() {
+            relaxed = None;
+            for (from, to, weight) in self.graph.all_edges() {
+                let next_distance = distances[&from] + weight.0;
+                if next_distance < distances[&to] {
+                    distances.insert(to, next_distance);
+                    parents.insert(to, from);
+                    relaxed = Some(to);
+                }
+            }
+
+            relaxed?;
+        }
+
+        // `relaxed` may only lead into the cycle, but walking back |V| parents from it lands on the cycle itself.
+        let mut node = relaxed?;
+        for _ in 0..self.graph.node_count() {
+            node = parents[&node];
+        }
+
+        let mut cycle = vec![node];
+        let mut next = parents[&node];
+        while next != node {
+            cycle.push(next);
+            next = parents[&next];
+        }
+        cycle.reverse();
+
+        Some(cycle)
+    }
+
+    /// The requested percentiles (each in `[0, 100]`) of the weights of edges between named nodes,
+    /// linearly interpolating between the closest weights. Returns nothing if there are no such edges.
+    pub fn weight_percentiles(&self, ps: &[f64]) -> Vec<f64> {
+        let mut weights = self
+            .graph
+            .all_edges()
+            .filter(|(a, b, _)| a.is_left() && b.is_left())
+            .map(|(_, _, weight)| weight.0)
+            .collect::<Vec<_>>();
+        if weights.is_empty() {
+            return vec![];
+        }
+        weights.sort_by(f64::total_cmp);
+
+        ps.iter()
+            .map(|p| {
+                let rank = p.clamp(0.0, 100.0) / 100.0 * (weights.len() - 1) as f64;
+                let (lower, upper) = (weights[rank.floor() as usize], weights[rank.ceil() as usize]);
+                lower + (upper - lower) * rank.fract()
+            })
+            .collect()
+    }
+
+    /// Keeps only the `k` cheapest (that is, strongest) incoming edges of every node, returning how many edges
+    /// were removed. Ties in weight keep edges from lower node ids. Edges to or from super-nodes are always kept,
+    /// and don't count toward `k`.
+    pub fn sparsify_incoming(&mut self, k: usize) -> usize {
+        let mut removed = vec![];
+        for node in self.graph.nodes().filter(|node| node.is_left()) {
+            let mut incoming = self
+                .graph
+                .edges_directed(node, Direction::Incoming)
+                .filter(|(source, _, _)| source.is_left())
+                .map(|(source, _, weight)| (weight.0, source))
+                .collect::<Vec<_>>();
+            if incoming.len() <= k {
+                continue;
+            }
+
+            incoming.sort_by(|(a, a_source), (b, b_source)| a.total_cmp(b).then_with(|| a_source.cmp(b_source)));
+            removed.extend(incoming[k..].iter().map(|&(_, source)| (source, node)));
+        }
+
+        for &(source, target) in &removed {
+            self.graph.remove_edge(source, target);
+        }
+
+        removed.len()
+    }
+
+    /// Contracts the nodes in `group` into a single fresh node named `new_name`, returning its id.
+    /// Edges incident to the group are rewired to the new node, where duplicate edges keep their minimum weight,
+    /// and edges within the group are dropped. `new_name` may reuse the name of a node in `group`, but naming
+    /// a node outside of it is an error, which leaves the network untouched.
+    pub fn contract_nodes(&mut self, group: &[&str], new_name: &str) -> Result<usize, ContractNodesError> {
+        let group = self.as_nodes(group)?;
+        if self.id_map.get_by_left(new_name).is_some_and(|&id| !group.contains(&Either::Left(id))) {
+            return Err(NetworkNameTakenError(new_name.to_string()).into());
+        }
+
+        let new_node = self.add_node();
+        let contracted = Either::Left(new_node);
+
+        let mut rewired_edges = vec![];
+        for &node in &group {
+            for next in self.graph.neighbors_directed(node, Direction::Outgoing) {
+                if !group.contains(&next) {
+                    rewired_edges.push((contracted, next, self.graph[(node, next)]));
+                }
+            }
+
+            for prev in self.graph.neighbors_directed(node, Direction::Incoming) {
+                if !group.contains(&prev) {
+                    rewired_edges.push((prev, contracted, self.graph[(prev, node)]));
+                }
+            }
+        }
+
+        for node in group {
+            self.graph.remove_node(node);
+            if let Either::Left(id) = node {
+                self.id_map.remove_by_right(&id);
+            }
+        }
+
+        for (a, b, weight) in rewired_edges {
+            match self.graph.edge_weight_mut(a, b) {
+                Some(existing) => {
+                    if weight.0 < existing.0 {
+                        *existing = weight;
+                    }
+                }
+                None => {
+                    self.graph.add_edge(a, b, weight);
+                }
+            }
+        }
+
+        self.id_map.insert(new_name.to_string(), new_node);
+
+        Ok(new_node)
+    }
+}
+
+impl<S: Eq + Hash + Copy + Ord> Network<TypedWeight, S> {
+    /// Splits the interaction types off of this network's edges, leaving a plain weighted network
+    /// over the same ids (see `GrowthOptions::edge_types`).
+    pub fn split_edge_types(self) -> (Network<Weight, S>, EdgeTypes) {
+        let mut graph = DiGraphMap::with_capacity_and_hasher(
+            self.graph.node_count(),
+            self.graph.edge_count(),
+            Xxh3Builder::new(),
+        );
+        for node in self.graph.nodes() {
+            graph.add_node(node);
+        }
+
+        let mut types = EdgeTypes::new();
+        for (a, b, edge) in self.graph.all_edges() {
+            graph.add_edge(a, b, edge.weight);
+            if let (Either::Left(a), Either::Left(b)) = (a, b) {
+                types.insert((a, b), edge.edge_type.clone());
+            }
+        }
+
+        let network = Network {
+            graph,
+            id_map: self.id_map,
+            max_id: self.max_id,
+            node_weights: self.node_weights,
+            default_node_weight: self.default_node_weight,
+            metadata: self.metadata,
+        };
+        (network, types)
+    }
+}
+
 impl<E: Clone> Network<E, Never> {
     pub fn cast_over_never<S: Eq + Hash + Copy + Ord>(self) -> Network<E, S> {
         let mut new_graph: GraphMap<Either<usize, _>, E, _, _> = DiGraphMap::with_capacity(self.graph.node_count(), self.graph.edge_count());
@@ -289,16 +1337,25 @@ impl<E: Clone> Network<E, Never> {
         Network {
             graph: new_graph,
             id_map: self.id_map,
-            max_id: self.max_id
+            max_id: self.max_id,
+            node_weights: self.node_weights,
+            default_node_weight: self.default_node_weight,
+            metadata: self.metadata,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{fs, io::Write};
+
+    use flate2::{write::GzEncoder, Compression};
     use petgraph::visit::IntoEdgeReferences;
 
-    use crate::parsing::weight::WeightDataFactory;
+    use crate::parsing::{
+        data::IgnoredDataFactory,
+        weight::{LogWeightDataFactory, TypedWeightDataFactory, WeightDataFactory},
+    };
 
     use super::*;
 
@@ -315,8 +1372,555 @@ mod tests {
         )
         .unwrap();
 
-        assert_eq!(network.graph.nodes().len(), 4);
-        assert_eq!(network.graph.edge_references().collect::<Vec<_>>().len(), 4);
+        assert_eq!(network.node_count(), 4);
+        assert_eq!(network.edge_count(), 4);
         assert_eq!(network.id_map.len(), 4);
     }
+
+    #[test]
+    fn parallel_matches_serial() {
+        let contents = (0..200)
+            .map(|idx| format!("N{}\tN{}\t0.{}", idx % 37, (idx * 7) % 53, idx % 10))
+            .chain(["# comment".to_string(), "".to_string(), "N1\tN2\t0.25".to_string()])
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let serial = Network::<_, ()>::from_lines::<WeightDataFactory, _>(
+            contents.lines().map(|line| Ok(line.to_string())),
+        )
+        .unwrap();
+        let parallel = Network::<_, ()>::from_str_parallel(
+            &contents,
+            7,
+            &WeightDataFactory,
+            BiHashMap::new(),
+            |_, idx| Some(idx),
+            ParseOptions::default(),
+        )
+        .unwrap();
+
+        let edges = |network: &Network<Weight, ()>| {
+            let mut edges = network
+                .graph
+                .edge_references()
+                .map(|(a, b, weight)| (a, b, weight.0.to_bits()))
+                .collect::<Vec<_>>();
+            edges.sort();
+            edges
+        };
+
+        assert_eq!(serial.id_map, parallel.id_map);
+        assert_eq!(serial.max_id, parallel.max_id);
+        assert_eq!(serial.graph.node_count(), parallel.graph.node_count());
+        assert_eq!(edges(&serial), edges(&parallel));
+    }
+
+    #[test]
+    fn structure_only() {
+        let lines = ["A\tB\t0.5", "B\tC\tnot-a-weight\textra", "C\tA"];
+        let network = Network::<(), ()>::from_lines::<IgnoredDataFactory, _>(
+            lines.into_iter().map(|line| Ok(line.to_string())),
+        )
+        .unwrap();
+
+        assert_eq!(network.node_count(), 3);
+        assert_eq!(network.edge_count(), 3);
+    }
+
+    #[test]
+    fn metadata_lines() {
+        let contents = "#@version 2.1\n# a plain comment\nA\tB\t0.5\n#@weight-type  probability\nB\tC\t0.5\n";
+        let expected = HashMap::from([
+            ("version".to_string(), "2.1".to_string()),
+            ("weight-type".to_string(), "probability".to_string()),
+        ]);
+
+        let network = Network::<Weight, ()>::from_lines_infallible::<WeightDataFactory>(
+            contents.lines().map(str::to_string),
+        )
+        .unwrap();
+        assert_eq!(network.metadata, expected);
+        assert_eq!(network.edge_count(), 2);
+
+        let parallel = Network::<Weight, ()>::from_str_parallel(
+            contents,
+            3,
+            &WeightDataFactory,
+            BiHashMap::new(),
+            |_, idx| Some(idx),
+            ParseOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(parallel.metadata, expected);
+
+        // Metadata above a skipped header is kept by both parsers.
+        let body = contents.replacen("#@version 2.1\n", "", 1);
+        let with_header = format!("#@version 2.1\nsource\ttarget\tweight\n{body}");
+        let options = ParseOptions { skip_header: true, ..ParseOptions::default() };
+        let serial = Network::<Weight, ()>::from_lines_with_options(
+            with_header.lines().map(|line| Ok(line.to_string())),
+            &WeightDataFactory,
+            BiHashMap::new(),
+            |_, idx| Some(idx),
+            options,
+        )
+        .unwrap();
+        let parallel = Network::<Weight, ()>::from_str_parallel(
+            &with_header,
+            3,
+            &WeightDataFactory,
+            BiHashMap::new(),
+            |_, idx| Some(idx),
+            options,
+        )
+        .unwrap();
+        assert_eq!(serial.metadata, expected);
+        assert_eq!(parallel.metadata, serial.metadata);
+        assert!(parallel.structural_eq(&serial));
+
+        assert_eq!(parse_metadata_line("%% key", "%%"), Some(("key", "")));
+        assert_eq!(parse_metadata_line("# version 2.1", METADATA_PREFIX), None);
+    }
+
+    #[test]
+    fn iter_names() {
+        let network = Network::<(), ()>::from_lines::<IgnoredDataFactory, _>(
+            ["Z\tB", "B\tA", "Q\tZ"].into_iter().map(|line| Ok(line.to_string())),
+        )
+        .unwrap();
+
+        let names = network.iter_names().collect::<Vec<_>>();
+        assert_eq!(names.iter().map(|&(name, _)| name.as_str()).collect::<Vec<_>>(), vec!["Z", "B", "A", "Q"]);
+        assert!(names.windows(2).all(|pair| pair[0].1 < pair[1].1));
+        assert!(names.iter().all(|&(name, id)| network.get_node(name).unwrap() == id));
+    }
+
+    #[test]
+    fn delimited_lines() {
+        let lines = |lines: &[&str]| lines.iter().map(|line| Ok(line.to_string())).collect::<Vec<_>>().into_iter();
+
+        let network = Network::<Weight, ()>::from_lines_delimited::<WeightDataFactory, _>(
+            lines(&["A,B,0.5", "# a comment", "B,C D,0.25"]),
+            ',',
+        )
+        .unwrap();
+        assert_eq!(network.edge_count(), 2);
+        assert_eq!(network.edge_weight_by_name("B", "C D").unwrap().unwrap().0, 0.25);
+
+        // Tabs are just part of the names, and the error says what lines were split on.
+        let err = Network::<Weight, ()>::from_lines_delimited::<WeightDataFactory, _>(lines(&["A\tB\t0.5"]), ',')
+            .unwrap_err();
+        assert!(matches!(err, NetworkParsingError::InvalidSizeError(1, 1, 3, _, ',')));
+        assert!(err.to_string().contains("split on ','"), "{err}");
+    }
+
+    #[test]
+    fn structural_eq() {
+        let network = |lines: &[&str]| {
+            Network::<Weight, ()>::from_lines::<WeightDataFactory, _>(lines.iter().map(|line| Ok(line.to_string())))
+                .unwrap()
+        };
+
+        // Line order changes which ids genes get, but not the network's structure.
+        let a = network(&["A\tB\t1", "B\tC\t2", "C\tD\t3"]);
+        let b = network(&["C\tD\t3", "B\tC\t2", "A\tB\t1"]);
+        assert_ne!(a.get_node("A").unwrap(), b.get_node("A").unwrap());
+        assert!(a.structural_eq(&b));
+        assert_eq!(a.structural_hash(), b.structural_hash());
+
+        let reweighted = network(&["C\tD\t3", "B\tC\t2", "A\tB\t0.5"]);
+        assert!(!a.structural_eq(&reweighted));
+        let reversed = network(&["A\tB\t1", "B\tC\t2", "D\tC\t3"]);
+        assert!(!a.structural_eq(&reversed));
+        assert_ne!(a.structural_hash(), reversed.structural_hash());
+    }
+
+    #[test]
+    fn to_edge_list() {
+        let network = Network::<(), ()>::from_lines::<IgnoredDataFactory, _>(
+            ["B\tC", "A\tB"].into_iter().map(|line| Ok(line.to_string())),
+        )
+        .unwrap();
+
+        assert_eq!(network.to_edge_list(), "A\tB\nB\tC\n");
+    }
+
+    #[test]
+    fn weight_percentiles() {
+        let network = Network::<Weight, ()>::from_lines::<WeightDataFactory, _>(
+            ["A\tB\t0.4", "B\tC\t0.1", "C\tD\t0.9", "D\tE\t0.2", "E\tF\t0.3"]
+                .into_iter()
+                .map(|line| Ok(line.to_string())),
+        )
+        .unwrap();
+
+        assert_eq!(network.weight_percentiles(&[0.0, 50.0, 100.0]), vec![0.1, 0.3, 0.9]);
+        assert!((network.weight_percentiles(&[10.0])[0] - 0.14).abs() < 1e-12);
+
+        let empty = Network::<Weight, ()>::from_lines::<WeightDataFactory, _>(std::iter::empty()).unwrap();
+        assert!(empty.weight_percentiles(&[50.0]).is_empty());
+    }
+
+    #[test]
+    fn attach_node_weights() {
+        let mut network = Network::<_, ()>::from_file::<WeightDataFactory>(Path::new(
+            "./tests/fixtures/triangle/interactome.txt",
+        ))
+        .unwrap();
+
+        network
+            .attach_node_weights(Path::new("./tests/fixtures/triangle/node-weights.txt"))
+            .unwrap();
+
+        assert_eq!(network.node_weight_by_name("B").unwrap(), 0.25);
+        // C is not in the file, and Z is not in the network.
+        assert_eq!(network.node_weight_by_name("C").unwrap(), 0.0);
+        assert_eq!(network.node_weights.len(), 2);
+    }
+
+    #[test]
+    fn contract_nodes() {
+        let mut network = Network::<_, ()>::from_lines::<WeightDataFactory, _>(
+            vec![
+                Ok("A\tB\t0.5".to_string()),
+                Ok("A\tC\t0.2".to_string()),
+                Ok("B\tC\t0.9".to_string()),
+                Ok("C\tD\t0.4".to_string()),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+
+        let contracted = network.contract_nodes(&["B", "C"], "BC").unwrap();
+
+        assert_eq!(network.get_node("BC").unwrap(), contracted);
+        assert!(network.get_node("B").is_err());
+        assert_eq!(network.graph.node_count(), 3);
+        assert_eq!(network.graph.edge_count(), 2);
+
+        let [a, bc, d] = network.as_nodes(&["A", "BC", "D"]).unwrap()[..] else {
+            unreachable!()
+        };
+        assert_eq!(network.graph.edge_weight(a, bc).unwrap().0, 0.2);
+        assert_eq!(network.graph.edge_weight(bc, d).unwrap().0, 0.4);
+        assert!(!network.graph.contains_edge(bc, bc));
+
+        // D is outside of the group, so its name can't be taken...
+        let before = network.to_edge_list();
+        assert!(matches!(
+            network.contract_nodes(&["A", "BC"], "D"),
+            Err(ContractNodesError::NetworkNameTakenError(NetworkNameTakenError(name))) if name == "D"
+        ));
+        assert_eq!(network.to_edge_list(), before);
+        assert_eq!(network.get_node("D").unwrap(), d.left().unwrap());
+
+        // ...but A's can.
+        let contracted = network.contract_nodes(&["A", "BC"], "A").unwrap();
+        assert_eq!(network.get_node("A").unwrap(), contracted);
+        assert_eq!(network.to_edge_list(), "A\tD\n");
+    }
+
+    #[test]
+    fn suspicious_duplicates() {
+        let network = Network::<_, ()>::from_lines::<WeightDataFactory, _>(
+            vec![
+                Ok("A B\tC\t0.5".to_string()),
+                Ok("A  B\tC\t0.5".to_string()),
+                Ok("AB\tC\t0.5".to_string()),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            network.find_suspicious_duplicates(),
+            vec![("A  B".to_string(), "A B".to_string())]
+        );
+    }
+
+    #[test]
+    fn add_edge_by_name() {
+        let mut network = Network::<_, ()>::from_lines::<WeightDataFactory, _>(
+            vec![Ok("A\tB\t0.5".to_string()), Ok("C\tD\t0.5".to_string())].into_iter(),
+        )
+        .unwrap();
+
+        network.add_edge_by_name("B", "C", Weight(0.25)).unwrap();
+        assert_eq!(network.edge_weight_by_name("B", "C").unwrap().map(|w| w.0), Some(0.25));
+        assert!(network.edge_weight_by_name("C", "B").unwrap().is_none());
+
+        // Duplicate edges overwrite the old weight
+        network.add_edge_by_name("A", "B", Weight(0.75)).unwrap();
+        assert_eq!(network.edge_weight_by_name("A", "B").unwrap().map(|w| w.0), Some(0.75));
+        assert_eq!(network.edge_count(), 3);
+
+        assert_eq!(network.add_edge_by_name("A", "E", Weight(0.1)).unwrap_err().0, "E");
+
+        let (_, e) = network.add_edge_by_name_allocating("A", "E", Weight(0.1));
+        assert_eq!(network.get_node("E").unwrap(), e);
+        assert_eq!(network.edge_weight_by_name("A", "E").unwrap().map(|w| w.0), Some(0.1));
+    }
+
+    #[test]
+    fn degree_table() {
+        let network = Network::<_, ()>::from_lines::<WeightDataFactory, _>(
+            ["A\t1", "B\t1", "C\t2", "K\tC", "1\t3", "2\t3", "3\tX", "3\tY"]
+                .into_iter()
+                .map(|line| Ok(format!("{line}\t0.123"))),
+        )
+        .unwrap();
+
+        let table = network.degree_table();
+        assert_eq!(table.len(), 9);
+        assert_eq!(table[0], ("3".to_string(), 2, 2));
+        assert_eq!(table[1], ("1".to_string(), 2, 1));
+        assert!(table.contains(&("K".to_string(), 0, 1)));
+        assert_eq!(table.last().unwrap(), &("Y".to_string(), 1, 0));
+    }
+
+    #[test]
+    fn sparsify_incoming() {
+        let mut network = Network::<_, ()>::from_lines_infallible::<WeightDataFactory>(
+            ["A\tX\t0.4", "B\tX\t0.1", "C\tX\t0.3", "D\tX\t0.2", "A\tB\t0.9"].into_iter().map(str::to_string),
+        )
+        .unwrap();
+        let x = Either::Left(network.get_node("X").unwrap());
+        network.graph.add_edge(Either::Right(()), x, Weight(1.0));
+
+        assert_eq!(network.sparsify_incoming(2), 2);
+        assert_eq!(network.to_edge_list(), "A\tB\nB\tX\nD\tX\n");
+        assert!(network.graph.contains_edge(Either::Right(()), x));
+    }
+
+    #[test]
+    fn negative_cycle() {
+        let parse = |lines: &[&str]| {
+            Network::<_, ()>::from_lines_infallible::<WeightDataFactory>(lines.iter().map(|line| line.to_string()))
+                .unwrap()
+        };
+
+        // Negative weights alone are fine.
+        assert!(parse(&["A\tB\t-1", "B\tC\t-1", "C\tA\t3"]).find_negative_cycle().is_none());
+
+        let network = parse(&["S\tA\t1", "A\tB\t1", "B\tC\t-1", "C\tA\t-1", "C\tT\t1"]);
+        let cycle = network
+            .find_negative_cycle()
+            .unwrap()
+            .into_iter()
+            .map(|node| network.id_from_idx(node.left().unwrap()).unwrap().as_str())
+            .collect::<Vec<_>>();
+        // The cycle may start from any of its nodes.
+        let start = cycle.iter().position(|&node| node == "A").unwrap();
+        assert_eq!([&cycle[start..], &cycle[..start]].concat(), vec!["A", "B", "C"]);
+    }
+
+    #[test]
+    fn gzipped_file() {
+        let folder = tempfile::tempdir().unwrap();
+        let (plain, gzipped) = (folder.path().join("interactome.txt"), folder.path().join("interactome.txt.gz"));
+        let contents = "A\tB\t0.5\n# comment\nB\tC\t0.25\n";
+        fs::write(&plain, contents).unwrap();
+        let mut encoder = GzEncoder::new(fs::File::create(&gzipped).unwrap(), Compression::default());
+        encoder.write_all(contents.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        let plain = Network::<Weight, ()>::from_file::<WeightDataFactory>(&plain).unwrap();
+        let network = Network::<Weight, ()>::from_file::<WeightDataFactory>(&gzipped).unwrap();
+        assert!(network.structural_eq(&plain));
+        assert_eq!(network.edge_weight_by_name("B", "C").unwrap().unwrap().0, 0.25);
+
+        let parallel = Network::<Weight, ()>::from_file_parallel::<WeightDataFactory>(&gzipped, false).unwrap();
+        assert!(parallel.structural_eq(&plain));
+    }
+
+    #[test]
+    fn duplicate_edge_policy() {
+        let parse = |policy| {
+            Network::<Weight, ()>::from_lines_with_policy::<WeightDataFactory, _>(
+                ["A\tB\t0.3", "B\tC\t1", "A\tB\t0.7", "A\tB\t0.2"].into_iter().map(|line| Ok(line.to_string())),
+                policy,
+            )
+        };
+        let weight = |policy| parse(policy).unwrap().edge_weight_by_name("A", "B").unwrap().unwrap().0;
+
+        assert_eq!(weight(DuplicateEdgePolicy::Overwrite), 0.2);
+        assert_eq!(weight(DuplicateEdgePolicy::KeepMin), 0.2);
+        assert_eq!(weight(DuplicateEdgePolicy::KeepMax), 0.7);
+        assert!((weight(DuplicateEdgePolicy::Mean) - 0.4).abs() < 1e-12);
+        assert_eq!(parse(DuplicateEdgePolicy::Mean).unwrap().edge_weight_by_name("B", "C").unwrap().unwrap().0, 1.0);
+        assert!(matches!(
+            parse(DuplicateEdgePolicy::Error),
+            Err(NetworkParsingError::DuplicateEdge(a, b, 3)) if a == "A" && b == "B"
+        ));
+    }
+
+    #[test]
+    fn parse_options() {
+        let lines = ["source,target,weight", "A,B,0.3", "B,C,1", "A,B,0.7", "A,B,0.2"];
+        let options = ParseOptions { delimiter: ',', skip_header: true, duplicates: DuplicateEdgePolicy::KeepMax };
+        let id_map = BiHashMap::from_iter([("B".to_string(), 4)]);
+
+        let serial = Network::<Weight, ()>::from_lines_with_options(
+            lines.iter().map(|line| Ok(line.to_string())),
+            &WeightDataFactory,
+            id_map.clone(),
+            |_, idx| Some(idx),
+            options,
+        )
+        .unwrap();
+        assert_eq!(serial.edge_weight_by_name("A", "B").unwrap().unwrap().0, 0.7);
+        assert_eq!(serial.get_node("B").unwrap(), 4);
+
+        let parallel = Network::<Weight, ()>::from_str_parallel(
+            &lines.join("\n"),
+            3,
+            &WeightDataFactory,
+            id_map,
+            |_, idx| Some(idx),
+            options,
+        )
+        .unwrap();
+        assert!(parallel.structural_eq(&serial));
+        assert_eq!(parallel.id_map, serial.id_map);
+        assert_eq!(parallel.edge_weight_by_name("A", "B").unwrap().unwrap().0, 0.7);
+
+        let error = ParseOptions { duplicates: DuplicateEdgePolicy::Error, ..options };
+        let parallel = Network::<Weight, ()>::from_str_parallel(
+            &lines.join("\n"),
+            3,
+            &WeightDataFactory,
+            BiHashMap::new(),
+            |_, idx| Some(idx),
+            error,
+        );
+        assert!(matches!(parallel, Err(NetworkParsingError::DuplicateEdge(_, _, 4))));
+    }
+
+    #[test]
+    fn duplicate_edge_policy_after_transform() {
+        // The log transform turns the highest raw weight into the lowest cost.
+        let network = Network::<Weight, ()>::from_lines_with_policy::<LogWeightDataFactory, _>(
+            ["A\tB\t0.3", "A\tB\t0.9", "A\tB\t0.5"].into_iter().map(|line| Ok(line.to_string())),
+            DuplicateEdgePolicy::KeepMin,
+        )
+        .unwrap();
+        let expected = LogWeightDataFactory(WeightDataFactory).from_strs(1, vec!["0.9".to_string()]).unwrap();
+        assert_eq!(network.edge_weight_by_name("A", "B").unwrap().unwrap(), &expected);
+
+        // Typed edges keep the type of the listing which is kept.
+        let typed = Network::<TypedWeight, ()>::from_lines_with_policy::<TypedWeightDataFactory, _>(
+            ["A\tB\t0.3\tphysical", "A\tB\t0.1\tgenetic"].into_iter().map(|line| Ok(line.to_string())),
+            DuplicateEdgePolicy::KeepMax,
+        )
+        .unwrap();
+        assert_eq!(typed.edge_weight_by_name("A", "B").unwrap().unwrap().edge_type, "physical");
+    }
+
+    #[test]
+    fn replicate_files() {
+        let replicates = [
+            "A\tB\t0.2\nB\tC\t0.5\nC\tD\t0.9\n",
+            "A\tB\t0.4\nB\tC\t0.7\n",
+            "A\tB\t0.9\nD\tE\t0.1\n",
+        ]
+        .map(|contents| {
+            let file = tempfile::NamedTempFile::new().unwrap();
+            fs::write(file.path(), contents).unwrap();
+            file
+        });
+        let paths = replicates.iter().map(|file| file.path()).collect::<Vec<_>>();
+
+        let mean = Network::<Weight, ()>::from_replicate_files::<WeightDataFactory>(&paths, 2, Aggregator::Mean).unwrap();
+        assert_eq!(mean.to_edge_list(), "A\tB\nB\tC\n");
+        assert!((mean.edge_weight_by_name("A", "B").unwrap().unwrap().0 - 0.5).abs() < 1e-12);
+        assert!((mean.edge_weight_by_name("B", "C").unwrap().unwrap().0 - 0.6).abs() < 1e-12);
+
+        let median =
+            Network::<Weight, ()>::from_replicate_files::<WeightDataFactory>(&paths, 1, Aggregator::Median).unwrap();
+        assert_eq!(median.edge_count(), 4);
+        assert_eq!(median.edge_weight_by_name("A", "B").unwrap().unwrap().0, 0.4);
+        assert_eq!(median.edge_weight_by_name("B", "C").unwrap().unwrap().0, 0.6);
+    }
+
+    #[test]
+    fn add_node_with_name() {
+        let mut network = Network::<_, ()>::from_lines::<WeightDataFactory, _>(
+            vec![Ok("A\tB\t0.5".to_string())].into_iter(),
+        )
+        .unwrap();
+
+        let id = network.add_node_with_name("C").unwrap();
+
+        assert_eq!(network.get_node("C").unwrap(), id);
+        assert_eq!(network.id_from_idx(id).unwrap(), "C");
+        assert!(network.graph.contains_node(Either::Left(id)));
+        assert!(network.add_node_with_name("A").is_err());
+    }
+
+    #[test]
+    fn shared_id_map() {
+        let id_map_file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(id_map_file.path(), "# gene\tid\nA\t3\nB\t0\nC\t7\nUNUSED\t1\n").unwrap();
+        let id_map = Network::<Weight, ()>::load_id_map(id_map_file.path()).unwrap();
+
+        let parse = |lines: &[&str]| {
+            Network::<_, ()>::from_lines_over_id_map::<WeightDataFactory, _>(
+                lines.iter().map(|line| Ok(line.to_string())),
+                id_map.clone(),
+                |_, idx| Some(idx),
+                false,
+            )
+            .unwrap()
+        };
+
+        let first = parse(&["A\tB\t0.5", "B\tC\t0.5", "C\tD\t0.5"]);
+        let second = parse(&["C\tD\t0.5", "B\tC\t0.5", "A\tB\t0.5"]);
+        assert_eq!(first.id_map, second.id_map);
+        assert_eq!(first.get_node("A").unwrap(), 3);
+        assert_eq!(first.get_node("C").unwrap(), 7);
+        // Unknown genes extend the map past its largest id
+        assert_eq!(first.get_node("D").unwrap(), 8);
+        assert!(first.get_node("UNUSED").is_err());
+
+        fs::write(id_map_file.path(), "A\t3\nB\t3\n").unwrap();
+        assert!(Network::<Weight, ()>::load_id_map(id_map_file.path()).is_err());
+    }
+
+    #[test]
+    fn skip_header() {
+        let lines = ["# comment", "", "source\ttarget\tweight", "A\tB\t0.5", "B\tC\t0.5"];
+        let parse = |skip_header| {
+            Network::<_, ()>::from_lines_over_id_map::<WeightDataFactory, _>(
+                lines.iter().map(|line| Ok(line.to_string())),
+                BiHashMap::new(),
+                |_, idx| Some(idx),
+                skip_header,
+            )
+        };
+
+        let network = parse(true).unwrap();
+        assert_eq!(network.graph.edge_count(), 2);
+        assert!(network.get_node("source").is_err());
+        assert!(matches!(parse(false), Err(NetworkParsingError::ParseDataError(_))));
+        // Only a failure on the first content line hints at skipping a header.
+        assert!(parse(false).unwrap_err().to_string().contains("skip_header"));
+        let bad_weight = Network::<Weight, ()>::from_lines::<WeightDataFactory, _>(
+            ["A\tB\t0.5", "B\tC\tweight"].into_iter().map(|line| Ok(line.to_string())),
+        )
+        .unwrap_err();
+        assert!(!bad_weight.to_string().contains("skip_header"), "{bad_weight}");
+
+        let options = ParseOptions { skip_header: true, ..ParseOptions::default() };
+        let parallel = Network::<_, ()>::from_str_parallel(
+            &lines.join("\n"),
+            2,
+            &WeightDataFactory,
+            BiHashMap::new(),
+            |_, idx| Some(idx),
+            options,
+        )
+        .unwrap();
+        assert_eq!(parallel.graph.edge_count(), 2);
+        assert_eq!(parallel.id_map, network.id_map);
+    }
 }
@@ -1,43 +1,218 @@
+use std::collections::HashMap;
+
 use super::data::DataFactory;
 use anyhow::anyhow;
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Weight(pub f64);
 
+#[derive(Clone, Copy, Debug, Default)]
 pub struct WeightDataFactory;
 impl DataFactory<Weight> for WeightDataFactory {
-    fn len() -> usize {
+    fn len(&self) -> usize {
         1
     }
 
-    fn err_str() -> String {
+    fn err_str(&self) -> String {
         "weight".to_string()
     }
 
-    fn from_strs(line: usize, strs: Vec<String>) -> Result<Weight, anyhow::Error> {
+    fn from_strs(&self, line: usize, strs: Vec<String>) -> Result<Weight, anyhow::Error> {
         let weight_str = &strs[0];
-        str::parse::<f64>(weight_str)
-            .map(Weight)
-            .map_err(|_| anyhow!("Line {line} has an invalid weight {weight_str}"))
+        let weight = str::parse::<f64>(weight_str)
+            .map_err(|_| anyhow!("Line {line} has an invalid weight {weight_str}"))?;
+
+        // NaNs and infinities can't be meaningfully compared during path-finding.
+        if !weight.is_finite() {
+            return Err(anyhow!("Line {line} has a non-finite weight {weight_str}"));
+        }
+
+        Ok(Weight(weight))
+    }
+}
+
+/// Reads the weight from the `column`th column of a line (1-indexed, so the first weight column is `3`),
+/// ignoring every other column. Lines may have any number of columns past `column`.
+#[derive(Clone, Copy, Debug)]
+pub struct ColumnWeightDataFactory {
+    column: usize,
+}
+
+impl ColumnWeightDataFactory {
+    /// Panics if `column` is before `3`, as the first two columns are the source and target.
+    pub fn new(column: usize) -> Self {
+        assert!(column >= 3, "the weight column must come after the source and target");
+        Self { column }
+    }
+}
+
+impl DataFactory<Weight> for ColumnWeightDataFactory {
+    fn len(&self) -> usize {
+        self.column - 2
+    }
+
+    fn err_str(&self) -> String {
+        format!("a weight in column {}", self.column)
+    }
+
+    fn from_strs(&self, line: usize, mut strs: Vec<String>) -> Result<Weight, anyhow::Error> {
+        WeightDataFactory.from_strs(line, vec![strs.swap_remove(self.column - 3)])
+    }
+
+    fn allows_extra_components(&self) -> bool {
+        true
     }
 }
 
-pub struct LogWeightDataFactory;
-impl DataFactory<Weight> for LogWeightDataFactory {
-    fn len() -> usize {
-        WeightDataFactory::len()
+/// Weights below this are raised to it before `LogWeightDataFactory`'s log transform, which is undefined at 0.
+pub const LOG_WEIGHT_FLOOR: f64 = 0.000_000_001;
+
+/// Log-transforms the weights read by `F`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LogWeightDataFactory<F = WeightDataFactory>(pub F);
+impl<F: DataFactory<Weight>> DataFactory<Weight> for LogWeightDataFactory<F> {
+    fn len(&self) -> usize {
+        self.0.len()
     }
 
-    fn err_str() -> String {
-        WeightDataFactory::err_str()
+    fn err_str(&self) -> String {
+        self.0.err_str()
     }
 
-    fn from_strs(line: usize, strs: Vec<String>) -> Result<Weight, anyhow::Error> {
-        let weight = WeightDataFactory::from_strs(line, strs)?;
+    fn from_strs(&self, line: usize, strs: Vec<String>) -> Result<Weight, anyhow::Error> {
+        let weight = self.0.from_strs(line, strs)?;
         // TODO: we use the magic value in Growing DAGs, 0.000000001 (most likely as to make this well-defined at 0,
         // but is there something better here that we can use?)
-        Ok(Weight(-f64::ln(
-            0.000_000_001_f64.max(weight.0) / f64::ln(10.0),
-        )))
+        let transformed = -f64::ln(
+            LOG_WEIGHT_FLOOR.max(weight.0) / f64::ln(10.0),
+        );
+
+        if !transformed.is_finite() {
+            return Err(anyhow!(
+                "Line {line} has weight {}, which is {transformed} after the log transform",
+                weight.0
+            ));
+        }
+
+        Ok(Weight(transformed))
+    }
+
+    fn allows_extra_components(&self) -> bool {
+        self.0.allows_extra_components()
+    }
+}
+
+/// Turns the "higher = better" similarities in `[0, 1]` read by `F` into the costs `1 - s`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct InvertedWeightDataFactory<F = WeightDataFactory>(pub F);
+impl<F: DataFactory<Weight>> DataFactory<Weight> for InvertedWeightDataFactory<F> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn err_str(&self) -> String {
+        self.0.err_str()
+    }
+
+    fn from_strs(&self, line: usize, strs: Vec<String>) -> Result<Weight, anyhow::Error> {
+        let weight = self.0.from_strs(line, strs)?;
+        if !(0.0..=1.0).contains(&weight.0) {
+            return Err(anyhow!("Line {line} has weight {}, which is not a similarity in [0, 1]", weight.0));
+        }
+
+        Ok(Weight(1.0 - weight.0))
+    }
+
+    fn allows_extra_components(&self) -> bool {
+        self.0.allows_extra_components()
+    }
+}
+
+/// A weight alongside the type of its interaction, such as `physical` or `genetic`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TypedWeight {
+    pub weight: Weight,
+    pub edge_type: String,
+}
+
+/// The interaction type of every edge between two genes (by id), as split off by `Network::split_edge_types`.
+pub type EdgeTypes = HashMap<(usize, usize), String>;
+
+/// Reads the weight with `F`, followed by the interaction type in the column right after `F`'s.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TypedWeightDataFactory<F = WeightDataFactory>(pub F);
+impl<F: DataFactory<Weight>> DataFactory<TypedWeight> for TypedWeightDataFactory<F> {
+    fn len(&self) -> usize {
+        self.0.len() + 1
+    }
+
+    fn err_str(&self) -> String {
+        format!("{} and edge type", self.0.err_str())
+    }
+
+    fn from_strs(&self, line: usize, mut strs: Vec<String>) -> Result<TypedWeight, anyhow::Error> {
+        let edge_type = strs.remove(self.0.len());
+        Ok(TypedWeight { weight: self.0.from_strs(line, strs)?, edge_type })
+    }
+
+    fn allows_extra_components(&self) -> bool {
+        self.0.allows_extra_components()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_nan() {
+        assert!(WeightDataFactory.from_strs(0, vec!["NaN".to_string()]).is_err());
+        assert!(LogWeightDataFactory(WeightDataFactory).from_strs(0, vec!["NaN".to_string()]).is_err());
+    }
+
+    #[test]
+    fn rejects_overflow() {
+        assert!(WeightDataFactory.from_strs(0, vec!["1e400".to_string()]).is_err());
+        assert!(LogWeightDataFactory(WeightDataFactory).from_strs(0, vec!["-inf".to_string()]).is_err());
+        assert!(LogWeightDataFactory(WeightDataFactory).from_strs(0, vec!["1e300".to_string()]).is_ok());
+    }
+
+    #[test]
+    fn column_weight() {
+        // The data columns of `A\tB\texperimental\t0.1\t0.25`.
+        let strs = || ["experimental", "0.1", "0.25"].map(String::from).to_vec();
+
+        assert_eq!(ColumnWeightDataFactory::new(5).len(), 3);
+        assert_eq!(ColumnWeightDataFactory::new(5).from_strs(0, strs()).unwrap().0, 0.25);
+        assert_eq!(ColumnWeightDataFactory::new(4).from_strs(0, strs()).unwrap().0, 0.1);
+        assert!(ColumnWeightDataFactory::new(3).from_strs(0, strs()).is_err());
+        // Columns past the sixteenth are as good as any other.
+        let wide = (0..40).map(|column| column.to_string()).collect::<Vec<_>>();
+        assert_eq!(ColumnWeightDataFactory::new(42).from_strs(0, wide).unwrap().0, 39.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "must come after the source and target")]
+    fn column_weight_before_data() {
+        ColumnWeightDataFactory::new(2);
+    }
+
+    #[test]
+    fn transform_ordering() {
+        let similarities = ["0.2", "0.5", "0.9"];
+        let costs = |factory: &dyn DataFactory<Weight>| {
+            similarities
+                .iter()
+                .map(|similarity| factory.from_strs(0, vec![similarity.to_string()]).unwrap().0)
+                .collect::<Vec<_>>()
+        };
+        let decreasing = |costs: Vec<f64>| costs.windows(2).all(|pair| pair[0] > pair[1]);
+
+        // Higher similarities should be cheaper, unless the weights are used as-is.
+        assert!(decreasing(costs(&LogWeightDataFactory(WeightDataFactory))));
+        assert!(decreasing(costs(&InvertedWeightDataFactory(WeightDataFactory))));
+        assert!(!decreasing(costs(&WeightDataFactory)));
+
+        assert!(InvertedWeightDataFactory(WeightDataFactory).from_strs(0, vec!["1.5".to_string()]).is_err());
     }
 }
@@ -1,10 +1,12 @@
 use std::{
+    collections::{HashMap, HashSet},
     fs::File,
     hash::{BuildHasher, Hash},
     io::{BufRead, BufReader},
     path::Path,
 };
 
+use anyhow::anyhow;
 use petgraph::{
     prelude::GraphMap,
     visit::{Dfs, Reversed},
@@ -59,6 +61,108 @@ pub fn read_lines(path: &Path) -> anyhow::Result<Vec<String>> {
         .collect())
 }
 
+/// The lines of a file, as read by `read_unique_lines`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UniqueLines {
+    /// Every non-blank line, in order of first appearance.
+    pub lines: Vec<String>,
+    /// How many lines repeated an earlier line.
+    pub duplicates: usize,
+    /// How many blank lines were skipped.
+    pub blanks: usize,
+}
+
+/// Like `read_lines`, but also drops (and warns about) lines repeating an earlier line,
+/// and treats whitespace-only lines as blank.
+pub fn read_unique_lines(path: &Path) -> anyhow::Result<UniqueLines> {
+    let lines = BufReader::new(File::open(path)?).lines().collect::<Result<Vec<_>, _>>()?;
+    let unique = dedup_lines(lines);
+
+    if unique.blanks > 0 {
+        log::debug!("Skipped {} blank lines in {}.", unique.blanks, path.display());
+    }
+
+    Ok(unique)
+}
+
+fn dedup_lines(lines: Vec<String>) -> UniqueLines {
+    let mut seen = HashSet::new();
+    let mut unique = UniqueLines { lines: vec![], duplicates: 0, blanks: 0 };
+
+    for (idx, line) in lines.into_iter().enumerate() {
+        if line.trim().is_empty() {
+            unique.blanks += 1;
+        } else if !seen.insert(line.clone()) {
+            log::warn!("Line {} ({line}) is a duplicate, and will be ignored.", idx + 1);
+            unique.duplicates += 1;
+        } else {
+            unique.lines.push(line);
+        }
+    }
+
+    unique
+}
+
+/// A source or target, along with its weight, if one was given.
+pub type Terminal = (String, Option<f64>);
+
+/// Reads sources or targets, where each name may be followed by a tab-separated weight. Blank lines are skipped.
+/// A name listed again with the same weight is dropped (and warned about), while a name listed again with
+/// a different weight is an error, as there is no telling which weight was meant.
+pub fn read_terminals(path: &Path) -> anyhow::Result<Vec<Terminal>> {
+    parse_terminals(BufReader::new(File::open(path)?).lines().collect::<Result<Vec<_>, _>>()?)
+}
+
+fn parse_terminals(lines: Vec<String>) -> anyhow::Result<Vec<Terminal>> {
+    let describe =
+        |weight: Option<f64>| weight.map_or("no weight".to_string(), |weight| format!("the weight {weight}"));
+    let mut terminals: Vec<Terminal> = vec![];
+    // The index in `terminals` and the line number of each name's first appearance.
+    let mut seen = HashMap::<String, (usize, usize)>::new();
+
+    for (idx, line) in lines.into_iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (name, weight) = parse_terminal(line)?;
+        match seen.get(&name) {
+            Some(&(first, first_line)) => {
+                let first_weight = terminals[first].1;
+                if first_weight != weight {
+                    return Err(anyhow!(
+                        "Line {} lists the terminal {name} with {}, but line {first_line} gave it {}",
+                        idx + 1,
+                        describe(weight),
+                        describe(first_weight)
+                    ));
+                }
+                log::warn!("Line {} repeats the terminal {name}, and will be ignored.", idx + 1);
+            }
+            None => {
+                seen.insert(name.clone(), (terminals.len(), idx + 1));
+                terminals.push((name, weight));
+            }
+        }
+    }
+
+    Ok(terminals)
+}
+
+fn parse_terminal(line: String) -> anyhow::Result<Terminal> {
+    match line.split_once('\t') {
+        None => Ok((line, None)),
+        Some((name, weight)) => {
+            let weight = weight
+                .parse::<f64>()
+                .ok()
+                .filter(|weight| weight.is_finite())
+                .ok_or_else(|| anyhow!("Terminal {name} has an invalid weight {weight}"))?;
+            Ok((name.to_string(), Some(weight)))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use petgraph::prelude::DiGraphMap;
@@ -77,4 +181,37 @@ mod tests {
         let graph: DiGraphMap<u32, ()> = DiGraphMap::from_edges(&[(0, 1), (1, 2), (2, 0)]);
         assert_eq!(get_descendents(&graph, 0), vec![2, 1]);
     }
+
+    #[test]
+    fn terminals() {
+        let lines = ["A", "B\t0.5", "C\t2"].map(String::from).to_vec();
+        assert_eq!(
+            parse_terminals(lines).unwrap(),
+            vec![("A".to_string(), None), ("B".to_string(), Some(0.5)), ("C".to_string(), Some(2.0))]
+        );
+
+        assert!(parse_terminals(vec!["A\thigh".to_string()]).is_err());
+    }
+
+    #[test]
+    fn duplicate_terminals() {
+        let lines = ["X\t0.5", "Y", "", "X\t0.5", "Y"].map(String::from).to_vec();
+        assert_eq!(parse_terminals(lines).unwrap(), vec![("X".to_string(), Some(0.5)), ("Y".to_string(), None)]);
+
+        for lines in [["X\t0.5", "X\t0.7"], ["X", "X\t0.7"]] {
+            let err = parse_terminals(lines.map(String::from).to_vec()).unwrap_err();
+            assert!(err.to_string().contains("line 1"), "{err}");
+        }
+    }
+
+    #[test]
+    fn unique_lines() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "X\nY\n\nX\n  \nZ\nX\nY\n").unwrap();
+
+        assert_eq!(
+            read_unique_lines(file.path()).unwrap(),
+            UniqueLines { lines: ["X", "Y", "Z"].map(String::from).to_vec(), duplicates: 3, blanks: 2 }
+        );
+    }
 }
@@ -0,0 +1,382 @@
+use std::{fs, process::Command};
+
+use growing_dags::parsing::network::{parse_metadata_line, METADATA_PREFIX};
+
+#[test]
+fn cyclic_dag_exit_code() {
+    let output = Command::new(env!("CARGO_BIN_EXE_growing-dags"))
+        .args(["-k", "1", "folder", "./tests/fixtures/cyclic"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(8));
+}
+
+#[test]
+fn output_dir_snapshots() {
+    let output_dir = tempfile::tempdir().unwrap();
+    let iterations = output_dir.path().join("iterations");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_growing-dags"))
+        .args(["-k", "3", "--output-dir"])
+        .arg(&iterations)
+        .args(["folder", "./tests/fixtures/fan"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // The fan DAG starts with 4 edges, and every path grown onto it here adds one or two.
+    let edge_counts = (1..=3)
+        .map(|i| fs::read_to_string(iterations.join(format!("iteration_{i}.tsv"))).unwrap().lines().count())
+        .collect::<Vec<_>>();
+    assert_eq!(edge_counts, vec![5, 7, 9]);
+    assert_eq!(fs::read_dir(&iterations).unwrap().count(), 3);
+}
+
+#[test]
+fn batch_folder() {
+    let root = tempfile::tempdir().unwrap();
+    for fixture in ["fan", "triangle"] {
+        let folder = root.path().join(fixture);
+        fs::create_dir(&folder).unwrap();
+        for file in ["interactome.txt", "dag.txt", "sources.txt", "targets.txt"] {
+            fs::copy(format!("./tests/fixtures/{fixture}/{file}"), folder.join(file)).unwrap();
+        }
+    }
+    fs::create_dir(root.path().join("incomplete")).unwrap();
+    fs::copy("./tests/fixtures/fan/interactome.txt", root.path().join("incomplete/interactome.txt")).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_growing-dags"))
+        .args(["-k", "2", "batch-folder"])
+        .arg(root.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let summary = String::from_utf8(output.stdout).unwrap();
+    let names = summary.lines().map(|line| line.split('\t').next().unwrap()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["fan", "triangle"]);
+
+    for (line, fixture) in summary.lines().zip(["fan", "triangle"]) {
+        let iterations = line.split('\t').nth(1).unwrap().parse::<usize>().unwrap();
+        let paths = fs::read_to_string(root.path().join(fixture).join("grown_paths.tsv")).unwrap();
+        assert_eq!(paths.lines().filter(|line| !line.starts_with('#')).count(), iterations);
+    }
+    assert!(!root.path().join("incomplete/grown_paths.tsv").exists());
+}
+
+#[test]
+fn batch_folder_output_dir_and_failures() {
+    let root = tempfile::tempdir().unwrap();
+    for (name, fixture) in [("broken", "fan"), ("fan", "fan")] {
+        let folder = root.path().join(name);
+        fs::create_dir(&folder).unwrap();
+        for file in ["interactome.txt", "dag.txt", "sources.txt", "targets.txt"] {
+            fs::copy(format!("./tests/fixtures/{fixture}/{file}"), folder.join(file)).unwrap();
+        }
+    }
+    fs::write(root.path().join("broken/interactome.txt"), "A\tB\tnot-a-weight\n").unwrap();
+    let output_dir = tempfile::tempdir().unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_growing-dags"))
+        .args(["-k", "2", "--output-dir"])
+        .arg(output_dir.path())
+        .arg("batch-folder")
+        .arg(root.path())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    // The broken folder doesn't stop the fan from growing.
+    let summary = String::from_utf8(output.stdout).unwrap();
+    let lines = summary.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0], "broken\tNA\tNA");
+    assert!(lines[1].starts_with("fan\t"), "{summary}");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Failed to grow in 1 folder(s): broken"), "{stderr}");
+
+    assert!(output_dir.path().join("fan/grown_paths.tsv").is_file());
+    assert!(!root.path().join("fan/grown_paths.tsv").exists());
+}
+
+#[test]
+fn wide_weight_column() {
+    // The fan's interactome, with its weights moved to column 20 behind other (unparseable) columns.
+    let folder = tempfile::tempdir().unwrap();
+    for file in ["dag.txt", "sources.txt", "targets.txt"] {
+        fs::copy(format!("./tests/fixtures/fan/{file}"), folder.path().join(file)).unwrap();
+    }
+    let interactome = fs::read_to_string("./tests/fixtures/fan/interactome.txt").unwrap();
+    let wide = interactome
+        .lines()
+        .map(|line| {
+            let [a, b, weight] = line.split('\t').collect::<Vec<_>>()[..] else { panic!("{line}") };
+            format!("{a}\t{b}\t{}{weight}\n", "NA\t".repeat(17))
+        })
+        .collect::<String>();
+    fs::write(folder.path().join("interactome.txt"), wide).unwrap();
+
+    let grow = |args: &[&str], folder: &std::path::Path| {
+        let output = Command::new(env!("CARGO_BIN_EXE_growing-dags"))
+            .args(["-k", "2"])
+            .args(args)
+            .arg("folder")
+            .arg(folder)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert_eq!(grow(&["--weight-column", "20"], folder.path()), grow(&[], "./tests/fixtures/fan".as_ref()));
+}
+
+#[test]
+fn negative_cycle_exit_code() {
+    let folder = tempfile::tempdir().unwrap();
+    fs::write(folder.path().join("interactome.txt"), "S\tA\t1\nA\tB\t1\nB\tA\t-2\nB\tT\t1\n").unwrap();
+    fs::write(folder.path().join("dag.txt"), "S\tA\n").unwrap();
+    fs::write(folder.path().join("sources.txt"), "S\n").unwrap();
+    fs::write(folder.path().join("targets.txt"), "T\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_growing-dags"))
+        .args(["-k", "1", "--transform", "identity", "--bellman-ford", "folder"])
+        .arg(folder.path())
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(10));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("A -> B -> A") || stderr.contains("B -> A -> B"), "{stderr}");
+}
+
+#[test]
+fn negative_weights_need_bellman_ford() {
+    let folder = tempfile::tempdir().unwrap();
+    fs::write(folder.path().join("interactome.txt"), "S\tA\t1\nS\tB\t3\nB\tA\t-3\nA\tT\t1\n").unwrap();
+    fs::write(folder.path().join("dag.txt"), "S\tA\n").unwrap();
+    fs::write(folder.path().join("sources.txt"), "S\n").unwrap();
+    fs::write(folder.path().join("targets.txt"), "T\n").unwrap();
+
+    let grow = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_growing-dags"))
+            .args(["-k", "1", "--transform", "identity"])
+            .args(args)
+            .arg("folder")
+            .arg(folder.path())
+            .output()
+            .unwrap()
+    };
+
+    let output = grow(&[]);
+    assert_eq!(output.status.code(), Some(11));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("--bellman-ford") && stderr.contains("B -> A (-3)"), "{stderr}");
+
+    // The cheapest path to add goes through the negative edge, which Dijkstra could miss.
+    let output = grow(&["--bellman-ford"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let path = stdout.lines().find(|line| !line.starts_with('#')).unwrap().split('\t').nth(2).unwrap();
+    assert_eq!(path, "S|B|A");
+}
+
+#[test]
+fn early_stop_warning() {
+    let grow = |k: &str| {
+        let output = Command::new(env!("CARGO_BIN_EXE_growing-dags"))
+            .args(["-k", k, "folder", "./tests/fixtures/triangle"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stderr).unwrap()
+    };
+
+    // The triangle only has room for a single path.
+    assert!(grow("5").contains("requested 5 growth steps, completed 1: no more source-to-target paths available"));
+    assert!(!grow("1").contains("growth steps"));
+}
+
+#[test]
+fn max_edges() {
+    let output_dir = tempfile::tempdir().unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_growing-dags"))
+        .args(["-k", "5", "--max-edges", "4", "--output-dir"])
+        .arg(output_dir.path())
+        .args(["folder", "./tests/fixtures/coin"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(!String::from_utf8(output.stderr).unwrap().contains("growth steps"));
+
+    // The coin DAG starts with 2 edges, and either of its paths adds another 2, leaving no room for the other.
+    assert_eq!(String::from_utf8(output.stdout).unwrap().lines().filter(|line| !line.starts_with('#')).count(), 1);
+    let dag = fs::read_to_string(output_dir.path().join("iteration_1.tsv")).unwrap();
+    assert_eq!(dag.lines().count(), 4);
+    assert!(!output_dir.path().join("iteration_2.tsv").exists());
+}
+
+#[test]
+fn consensus_growth_flags() {
+    let consensus = |flags: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_growing-dags"))
+            .args(["-k", "1", "--consensus", "8", "--consensus-fraction", "0", "--threads", "2"])
+            .args(flags)
+            .args(["folder", "./tests/fixtures/coin"])
+            .output()
+            .unwrap()
+    };
+    let edges = |output: std::process::Output| {
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap().lines().filter(|line| !line.starts_with('#')).count()
+    };
+
+    // Every run grows one of the coin's two paths onto its 2 edges, but with --until the target is already connected.
+    assert_eq!(edges(consensus(&[])), 6);
+    assert_eq!(edges(consensus(&["--until", "all-targets-connected"])), 2);
+    assert_eq!(edges(consensus(&["--max-edges", "3"])), 2);
+
+    // There are no iterations to snapshot.
+    let output = consensus(&["--output-dir", "unused"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("--output-dir is not supported by --consensus"));
+}
+
+#[test]
+fn seeds_growth_flags() {
+    // A copy of the coin, whose source is weighted.
+    let weighted = tempfile::tempdir().unwrap();
+    for file in ["dag.txt", "targets.txt"] {
+        fs::copy(format!("./tests/fixtures/coin/{file}"), weighted.path().join(file)).unwrap();
+    }
+    fs::write(weighted.path().join("sources.txt"), "A\t0.5\n").unwrap();
+
+    let seeds = |flags: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_growing-dags"))
+            .args(["-k", "2"])
+            .args(flags)
+            .args(["seeds", "./tests/fixtures/coin/interactome.txt", "./tests/fixtures/coin"])
+            .arg(weighted.path())
+            .output()
+            .unwrap()
+    };
+    let paths = |output: std::process::Output| {
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap().lines().filter(|line| !line.starts_with('#')).count()
+    };
+
+    // Both of the coin's paths are grown onto each seed, unless growth stops before either is.
+    assert_eq!(paths(seeds(&[])), 4);
+    assert_eq!(paths(seeds(&["--until", "all-targets-connected"])), 0);
+    assert_eq!(paths(seeds(&["--max-edges", "3"])), 0);
+
+    let output = seeds(&["--output-dir", "unused"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("not supported by seeds"));
+}
+
+#[test]
+fn inline_terminals() {
+    let fan = |file: &str| format!("./tests/fixtures/fan/{file}");
+    let output = Command::new(env!("CARGO_BIN_EXE_growing-dags"))
+        .args(["-k", "1", "files", &fan("interactome.txt"), &fan("dag.txt")])
+        .args(["--source", "A", "--source", "X", "--target", "T"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8(output.stdout).unwrap().lines().filter(|line| !line.starts_with('#')).count(), 1);
+
+    // Inline terminals replace both files.
+    let output = Command::new(env!("CARGO_BIN_EXE_growing-dags"))
+        .args(["-k", "1", "files", &fan("interactome.txt"), &fan("dag.txt"), &fan("sources.txt")])
+        .args(["--source", "A", "--target", "T"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn output_header() {
+    let output = Command::new(env!("CARGO_BIN_EXE_growing-dags"))
+        .args(["-k", "2", "--cost", "path", "folder", "./tests/fixtures/triangle"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let header = stdout.lines().map_while(|line| parse_metadata_line(line, METADATA_PREFIX)).collect::<Vec<_>>();
+    assert_eq!(header, vec![("transform", "log"), ("log_weight_floor", "1e-9"), ("cost", "path"), ("k", "2")]);
+    assert_eq!(stdout.lines().count(), header.len() + 1);
+}
+
+#[test]
+fn combined_cost_header() {
+    let output = Command::new(env!("CARGO_BIN_EXE_growing-dags"))
+        .args(["-k", "1", "--node-weights", "./tests/fixtures/triangle/node-weights.txt"])
+        .args(["--node-weight-alpha", "0.5", "--length-penalty", "0.25", "folder", "./tests/fixtures/triangle"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let header = stdout.lines().map_while(|line| parse_metadata_line(line, METADATA_PREFIX)).collect::<Vec<_>>();
+    assert_eq!(
+        header,
+        vec![
+            ("transform", "log"),
+            ("log_weight_floor", "1e-9"),
+            ("cost", "combined"),
+            ("node_weight_alpha", "0.5"),
+            ("length_penalty", "0.25"),
+            ("k", "1"),
+        ]
+    );
+}
+
+#[test]
+fn dag_weights() {
+    let output_dir = tempfile::tempdir().unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_growing-dags"))
+        .args(["-k", "1", "--dag-weights", "--output-dir"])
+        .arg(output_dir.path())
+        .args(["folder", "./tests/fixtures/triangle"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // Every triangle edge has a weight of 1, which the (default) log transform turns into a cost of ln(ln(10)).
+    let dag_weights = fs::read_to_string(output_dir.path().join("dag_weights.tsv")).unwrap();
+    let lines = dag_weights.lines().map(|line| line.split('\t').collect::<Vec<_>>()).collect::<Vec<_>>();
+    assert_eq!(lines.len(), 3);
+    for (line, edge) in lines.iter().zip([["A", "B"], ["A", "C"], ["B", "C"]]) {
+        assert_eq!(line[..2], edge);
+        assert!((line[2].parse::<f64>().unwrap() - 10_f64.ln().ln()).abs() < 1e-12);
+        assert_eq!(line[3], "1");
+    }
+}
+
+#[test]
+fn path_cost_reports_enumerated_paths() {
+    let enumerated = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_growing-dags"))
+            .args(["-k", "2"])
+            .args(args)
+            .args(["folder", "./tests/fixtures/fan"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .lines()
+            .find(|line| line.ends_with("source-target paths while scoring candidates."))
+            .map(str::to_string)
+    };
+
+    let serial = enumerated(&["--cost", "path"]).unwrap();
+    // Paths enumerated on other threads count all the same.
+    assert_eq!(enumerated(&["--cost", "path", "--threads", "2"]), Some(serial));
+    assert_eq!(enumerated(&["--cost", "edge"]), None);
+}
@@ -3,8 +3,13 @@ use std::path::Path;
 use either::Either;
 use growing_dags::{
     alg::{
-        cost::EdgeCost,
-        grow::{grow, GrowthCache},
+        consensus::{consensus, edge_support},
+        cost::{Cost, EdgeCost, LengthPenaltyCost, RecencyCost},
+        grow::{
+            grow, grow_k, produce_dag, produce_dag_candidates, produce_dag_with_diagnostics, GrowthCache,
+            GrowthOptions, StopCondition,
+        },
+        seed::{grow_seeds, Seed},
     },
     parsing::{
         dag::PartialDag,
@@ -16,16 +21,13 @@ use growing_dags::{
     util::read_lines,
 };
 use never::Never;
-use petgraph::visit::IntoEdgeReferences;
 
 struct Fixture {
     interactome: Interactome<Weight>,
     dag: PartialDag<()>,
-    sources: Vec<String>,
-    targets: Vec<String>,
 }
 
-fn grab_fixture<F: DataFactory<Weight>>(folder: &Path) -> Fixture {
+fn grab_fixture<F: DataFactory<Weight> + Default>(folder: &Path) -> Fixture {
     let interactome_path = folder.join("interactome.txt");
     let dag_path = folder.join("dag.txt");
     let sources_path = folder.join("sources.txt");
@@ -41,6 +43,7 @@ fn grab_fixture<F: DataFactory<Weight>>(folder: &Path) -> Fixture {
         Network::<(), Never>::from_file_using_id_map::<EmptyTupleDataFactory>(
             &dag_path,
             &interactome.inner_network.id_map,
+            false,
         )
         .unwrap(),
         &sources,
@@ -48,12 +51,7 @@ fn grab_fixture<F: DataFactory<Weight>>(folder: &Path) -> Fixture {
     )
     .unwrap();
 
-    Fixture {
-        interactome,
-        dag,
-        sources,
-        targets,
-    }
+    Fixture { interactome, dag }
 }
 
 #[ctor::ctor]
@@ -63,30 +61,15 @@ fn init() {
 
 #[test]
 fn test_triangle() {
-    let Fixture {
-        interactome,
-        mut dag,
-        sources: _,
-        targets: _,
-    } = grab_fixture::<WeightDataFactory>(Path::new("./tests/fixtures/triangle"));
+    let Fixture { interactome, mut dag } = grab_fixture::<WeightDataFactory>(Path::new("./tests/fixtures/triangle"));
 
     // two extra edges for the super-source and the super-target
     assert_eq!(
-        interactome
-            .inner_network
-            .graph
-            .edge_references()
-            .collect::<Vec<_>>()
-            .len(),
+        interactome.inner_network.edge_count(),
         3 + 2
     );
     assert_eq!(
-        dag.0
-            .inner_network
-            .graph
-            .edge_references()
-            .collect::<Vec<_>>()
-            .len(),
+        dag.0.inner_network.edge_count(),
         4
     );
 
@@ -95,12 +78,243 @@ fn test_triangle() {
 
     assert_eq!(grow_weight, Some((1.0, vec![Either::Left(1), Either::Left(2)])));
     assert_eq!(
-        dag.0
-            .inner_network
-            .graph
-            .edge_references()
-            .collect::<Vec<_>>()
-            .len(),
+        dag.0.inner_network.edge_count(),
         3 + 2 // this doesn't post-process remove the super nodes.
     );
 }
+
+#[test]
+fn test_numeric_gene_names() {
+    let Fixture { interactome, mut dag } = grab_fixture::<WeightDataFactory>(Path::new("./tests/fixtures/numeric"));
+
+    // Ids are handed out in the order genes are read, so no gene's id matches its name.
+    for (name, id) in [("2", 0), ("3", 1), ("1", 2)] {
+        assert_eq!(interactome.inner_network.get_node(name).unwrap(), id);
+    }
+
+    let mut cache = GrowthCache::new(interactome.clone());
+    let (_, path) = grow(&interactome, &mut dag, &mut cache, &mut EdgeCost).unwrap().unwrap();
+
+    assert_eq!(path, vec![Either::Left(0), Either::Left(2)]);
+    assert_eq!(interactome.strip_super_and_name(&path).unwrap(), vec!["2", "1"]);
+    assert_eq!(dag.0.inner_network.to_edge_list(), "2\t1\n2\t3\n3\t1\n");
+}
+
+#[test]
+fn test_triangle_available_edges() {
+    let Fixture { interactome, mut dag } = grab_fixture::<WeightDataFactory>(Path::new("./tests/fixtures/triangle"));
+
+    let mut cache = GrowthCache::new(interactome.clone());
+    // The DAG already has A -> B and A -> C.
+    assert_eq!(cache.available_edges(&dag), vec![("B".to_string(), "C".to_string(), 1.0)]);
+
+    grow(&interactome, &mut dag, &mut cache, &mut EdgeCost).unwrap().unwrap();
+    assert!(cache.available_edges(&dag).is_empty());
+    assert!(GrowthCache::lazy().available_edges(&dag).is_empty());
+}
+
+#[test]
+fn test_triangle_candidates() {
+    let Fixture { interactome, dag } = grab_fixture::<WeightDataFactory>(Path::new("./tests/fixtures/triangle"));
+
+    let mut cache = GrowthCache::new(interactome.clone());
+    let best = produce_dag(&interactome, &dag, &mut cache, &mut EdgeCost).unwrap();
+
+    let mut cache = GrowthCache::new(interactome.clone());
+    let ranked = produce_dag_candidates(&interactome, &dag, &mut cache, &mut EdgeCost).unwrap();
+
+    assert!(!ranked.is_empty());
+    assert_eq!(ranked.first().cloned(), best);
+    assert!(ranked.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+
+    // B -> C is the only edge of the triangle which isn't already in the DAG.
+    let candidates = ranked
+        .iter()
+        .map(|(cost, path)| (*cost, path.iter().map(|&node| interactome.name_from_idx(node).unwrap()).collect()))
+        .collect::<Vec<(f64, Vec<String>)>>();
+    assert_eq!(candidates, vec![(1.0, vec!["B".to_string(), "C".to_string()])]);
+}
+
+#[test]
+fn test_triangle_diagnostics() {
+    let Fixture { interactome, dag } = grab_fixture::<WeightDataFactory>(Path::new("./tests/fixtures/triangle"));
+
+    let mut cache = GrowthCache::new(interactome.clone());
+    let best = produce_dag(&interactome, &dag, &mut cache, &mut EdgeCost).unwrap();
+    let (path, diagnostics) = produce_dag_with_diagnostics(&interactome, &dag, &mut cache, &mut EdgeCost).unwrap();
+
+    assert_eq!(path, best);
+    assert!(diagnostics.candidate_nodes > 0);
+    assert!(diagnostics.candidate_edges > 0);
+    assert!(diagnostics.relaxations > 0);
+}
+
+#[test]
+fn test_seeds_match_independent_runs() {
+    seeds_match_independent_runs(EdgeCost);
+    // Recency is tracked per DAG, so it must not carry over from one seed to the next.
+    seeds_match_independent_runs(RecencyCost::new(EdgeCost, 0.9));
+}
+
+fn seeds_match_independent_runs<C: Cost + Clone>(cost: C) {
+    let network =
+        Network::<Weight, Never>::from_file::<WeightDataFactory>(Path::new("./tests/fixtures/fan/interactome.txt"))
+            .unwrap();
+
+    let seed = |dag: &[&str]| Seed {
+        dag: Network::from_lines_using_id_map::<EmptyTupleDataFactory, _>(
+            dag.iter().map(|line| Ok(line.to_string())),
+            &network.id_map,
+            false,
+        )
+        .unwrap(),
+        sources: vec![("A".to_string(), None)],
+        targets: vec![("T".to_string(), None)],
+    };
+    let seed_dags: [&[&str]; 2] = [&["A\tX", "X\tT"], &["A\tY", "Y\tT"]];
+
+    let seeds = seed_dags.iter().map(|dag| seed(dag)).collect();
+    let grown = grow_seeds(&network, seeds, &cost, &GrowthOptions::default(), None, 3).unwrap();
+    assert_eq!(grown.len(), 2);
+
+    for (dag, (grown_dag, grown_paths)) in seed_dags.iter().zip(grown) {
+        let Seed { dag: independent_dag, .. } = seed(dag);
+        let (sources, targets) = (["A".to_string()], ["T".to_string()]);
+        let interactome =
+            Interactome::attach_sources_and_targets(network.clone(), &sources, &targets, true).unwrap();
+        let mut independent_dag = PartialDag::new(independent_dag, &sources, &targets).unwrap();
+        let mut cache = GrowthCache::new(interactome.clone());
+        let independent_paths =
+            grow_k(&interactome, &mut independent_dag, &mut cache, &mut cost.clone(), 3, None).unwrap();
+
+        assert_eq!(grown_paths, independent_paths);
+        assert_eq!(
+            grown_dag.0.inner_network.graph.edge_count(),
+            independent_dag.0.inner_network.graph.edge_count()
+        );
+    }
+}
+
+#[test]
+fn test_candidates_deterministic() {
+    let Fixture { interactome, dag } = grab_fixture::<WeightDataFactory>(Path::new("./tests/fixtures/fan"));
+
+    let mut cache = GrowthCache::new(interactome.clone());
+    let first = produce_dag_candidates(&interactome, &dag, &mut cache, &mut EdgeCost).unwrap();
+    assert!(first.len() > 1);
+
+    for _ in 0..10 {
+        let mut cache = GrowthCache::new(interactome.clone());
+        assert_eq!(produce_dag_candidates(&interactome, &dag, &mut cache, &mut EdgeCost).unwrap(), first);
+    }
+}
+
+#[test]
+fn test_added_interactome_edge() {
+    let network = Network::<Weight, Never>::from_lines::<WeightDataFactory, _>(
+        ["A\tC\t1", "A\tX\t1", "X\tC\t1", "A\tY\t3", "Y\tC\t3"]
+            .into_iter()
+            .map(|line| Ok(line.to_string())),
+    )
+    .unwrap();
+    let sources = vec!["A".to_string()];
+    let targets = vec!["C".to_string()];
+    let interactome = Interactome::attach_sources_and_targets(network, &sources, &targets, true).unwrap();
+    let mut dag = PartialDag::new(
+        Network::from_lines_using_id_map::<EmptyTupleDataFactory, _>(
+            vec![Ok("A\tC".to_string())].into_iter(),
+            &interactome.inner_network.id_map,
+            false,
+        )
+        .unwrap(),
+        &sources,
+        &targets,
+    )
+    .unwrap();
+
+    let mut cache = GrowthCache::new(interactome.clone());
+    let (weight, _) = grow(&interactome, &mut dag, &mut cache, &mut EdgeCost).unwrap().unwrap();
+    assert_eq!(weight, 2.0);
+
+    // Without this, the next path would be A -> Y -> C.
+    cache.add_interactome_edge("A", "Z", Weight(0.25));
+    cache.add_interactome_edge("Z", "C", Weight(0.25));
+
+    let (weight, path) = grow(&interactome, &mut dag, &mut cache, &mut EdgeCost).unwrap().unwrap();
+    assert_eq!(weight, 0.5);
+    assert_eq!(
+        path.into_iter()
+            .filter_map(|node| node.left())
+            .map(|node| dag.0.inner_network.id_from_idx(node).unwrap().clone())
+            .collect::<Vec<_>>(),
+        vec!["A", "Z", "C"]
+    );
+}
+
+#[test]
+fn test_consensus() {
+    let Fixture { interactome, dag } = grab_fixture::<WeightDataFactory>(Path::new("./tests/fixtures/coin"));
+
+    let edges = consensus(&interactome, &dag, &EdgeCost, &GrowthOptions::default(), 1, 64, 0.0).unwrap();
+    let frequency = |a: &str, b: &str| {
+        edges
+            .iter()
+            .find(|(x, y, _)| x == a && y == b)
+            .map_or(0.0, |(_, _, frequency)| *frequency)
+    };
+
+    // The initial DAG is always present,
+    assert_eq!(frequency("A", "M"), 1.0);
+    assert_eq!(frequency("M", "T"), 1.0);
+    // but A -> X -> M and M -> Y -> T tie, so they should be split across runs.
+    assert_eq!(frequency("A", "X"), frequency("X", "M"));
+    assert_eq!(frequency("A", "X") + frequency("M", "Y"), 1.0);
+    assert!((0.25..=0.75).contains(&frequency("A", "X")));
+
+    let majority = consensus(&interactome, &dag, &EdgeCost, &GrowthOptions::default(), 1, 64, 0.9).unwrap();
+    assert_eq!(majority.len(), 2);
+
+    // Every run grows under the given options: here, T is already connected, so no run grows at all.
+    let options = GrowthOptions { until: StopCondition::AllTargetsConnected, ..Default::default() };
+    let connected = consensus(&interactome, &dag, &EdgeCost, &options, 1, 64, 0.0).unwrap();
+    assert_eq!(connected, vec![("A".into(), "M".into(), 1.0), ("M".into(), "T".into(), 1.0)]);
+}
+
+#[test]
+fn test_edge_support_across_costs() {
+    let lines = |lines: &[&str]| lines.iter().map(|line| Ok(line.to_string())).collect::<Vec<_>>().into_iter();
+    let (sources, targets) = (["A".to_string()], ["D".to_string()]);
+
+    // Against the DAG A -> M -> D, A -> B -> C -> D has the cheapest edges, but A -> E -> M is a hop shorter.
+    let network = Network::from_lines::<WeightDataFactory, _>(lines(&[
+        "A\tM\t1", "M\tD\t1", "A\tB\t0.1", "B\tC\t0.1", "C\tD\t0.1", "A\tE\t0.25", "E\tM\t0.25",
+    ]))
+    .unwrap();
+    let interactome = Interactome::attach_sources_and_targets(network, &sources, &targets, true).unwrap();
+    let dag = PartialDag::new(
+        Network::from_lines_using_id_map::<EmptyTupleDataFactory, _>(
+            lines(&["A\tM", "M\tD"]),
+            &interactome.inner_network.id_map,
+            false,
+        )
+        .unwrap(),
+        &sources,
+        &targets,
+    )
+    .unwrap();
+
+    let (mut by_edges, mut by_length) = (dag.clone(), dag.clone());
+    grow_k(&interactome, &mut by_edges, &mut GrowthCache::lazy(), &mut EdgeCost, 1, None).unwrap();
+    grow_k(&interactome, &mut by_length, &mut GrowthCache::lazy(), &mut LengthPenaltyCost::new(EdgeCost, 1.0), 1, None)
+        .unwrap();
+
+    let support = edge_support(&[by_edges, by_length])
+        .into_iter()
+        .map(|(a, b, count)| (format!("{a}->{b}"), count))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        support,
+        [("A->M", 2), ("M->D", 2), ("A->B", 1), ("A->E", 1), ("B->C", 1), ("C->D", 1), ("E->M", 1)]
+            .map(|(edge, count)| (edge.to_string(), count))
+    );
+}